@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::input::{self, fields, parse_field};
+use crate::runner::DayReport;
+
+pub const ANSWERS_FILE: &str = "answers";
+
+pub struct Answers {
+    expected: HashMap<(u32, u8), u64>,
+}
+
+impl Answers {
+    fn parse_line(line: &str) -> Result<Option<((u32, u8), u64)>> {
+        let line = line.split('#').next().unwrap_or("");
+        match fields(line).as_slice() {
+            [] => Ok(None),
+            &[(day_offset, day), (part_offset, part), (value_offset, value)] => {
+                let day = parse_field::<u32>(day_offset, day)?;
+                let part = match part {
+                    "1" => 1,
+                    "2" => 2,
+                    part => return Err(Error::parse(part_offset + 1, part, format!("invalid part '{}'", part))),
+                };
+                let value = parse_field::<u64>(value_offset, value)?;
+                Ok(Some(((day, part), value)))
+            }
+            _ => Err(Error::parse(1, line.trim(), format!("expected '<day> <part> <answer>', got '{}'", line.trim()))),
+        }
+    }
+
+    pub fn parse<R: BufRead>(reader: R) -> Result<Answers> {
+        let mut expected = HashMap::new();
+        for (line_index, line) in input::lines(reader).enumerate() {
+            let line = line?;
+            let entry = Self::parse_line(&line).map_err(|e| e.at_line(line_index + 1, &line))?;
+            if let Some(((day, part), value)) = entry {
+                if expected.insert((day, part), value).is_some() {
+                    return Err(Error::parse(1, line.trim(), format!("duplicate answer for day {} part {}", day, part))
+                        .at_line(line_index + 1, &line));
+                }
+            }
+        }
+        Ok(Answers { expected })
+    }
+
+    pub fn load(path: &Path) -> Result<Answers> {
+        Answers::parse(BufReader::new(File::open(path)?))
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<u64> {
+        self.expected.get(&(day, part)).copied()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Fail { expected: u64, actual: std::result::Result<u64, String> },
+    Missing,
+}
+
+pub struct CheckResult {
+    pub day: u32,
+    pub part: u8,
+    pub actual: std::result::Result<u64, String>,
+    pub status: CheckStatus,
+}
+
+pub fn check(reports: &[DayReport], answers: &Answers) -> Vec<CheckResult> {
+    reports
+        .iter()
+        .flat_map(|report| report.parts.iter().map(move |part| (report.day, part)))
+        .map(|(day, part)| {
+            let status = match (answers.get(day, part.part), &part.outcome) {
+                (None, _) => CheckStatus::Missing,
+                (Some(expected), Ok(actual)) if expected == *actual => CheckStatus::Pass,
+                (Some(expected), actual) => CheckStatus::Fail { expected, actual: actual.clone() },
+            };
+            CheckResult { day, part: part.part, actual: part.outcome.clone(), status }
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::input::{self, fields, parse_field};
+use crate::registry::Day;
+use crate::solution::Solution;
+
+pub struct BenchOptions {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> BenchOptions {
+        BenchOptions { warmup: 2, iterations: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+        let nanos: Vec<f64> = sorted.iter().map(|sample| sample.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / nanos.len() as f64;
+        let variance = nanos.iter().map(|n| (n - mean) * (n - mean)).sum::<f64>() / nanos.len() as f64;
+        Some(Stats {
+            samples: sorted.len(),
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        })
+    }
+}
+
+pub struct BenchReport {
+    pub day: u32,
+    pub name: &'static str,
+    pub phases: Vec<(&'static str, Stats)>,
+}
+
+fn measure<T, F>(options: &BenchOptions, mut run: F) -> Result<Stats>
+where F: FnMut() -> Result<T> {
+    for _ in 0..options.warmup {
+        run()?;
+    }
+    let iterations = options.iterations.max(1);
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        run()?;
+        samples.push(start.elapsed());
+    }
+    Ok(Stats::from_samples(&samples).expect("at least one sample"))
+}
+
+pub fn bench_day(day: &Day, input: &str, options: &BenchOptions) -> Result<BenchReport> {
+    let parse = || (day.parse)(&mut input.as_bytes());
+    let parse_stats = measure(options, parse)?;
+    let solution: Box<dyn Solution> = parse()?;
+    let part_one_stats = measure(options, || solution.part_one())?;
+    let part_two_stats = measure(options, || solution.part_two())?;
+    Ok(BenchReport {
+        day: day.number,
+        name: day.name,
+        phases: vec![("parse", parse_stats), ("part1", part_one_stats), ("part2", part_two_stats)],
+    })
+}
+
+pub struct Baseline {
+    medians: HashMap<(u32, String), Duration>,
+}
+
+impl Baseline {
+    fn parse_line(line: &str) -> Result<Option<((u32, String), Duration)>> {
+        match fields(line).as_slice() {
+            [] => Ok(None),
+            &[(day_offset, day), (_, phase), (median_offset, median_ns)] => {
+                let day = parse_field::<u32>(day_offset, day)?;
+                let median_ns = parse_field::<u64>(median_offset, median_ns)?;
+                Ok(Some(((day, phase.to_string()), Duration::from_nanos(median_ns))))
+            }
+            _ => Err(Error::parse(1, line.trim(), format!("expected '<day> <phase> <median_ns>', got '{}'", line))),
+        }
+    }
+
+    pub fn parse<R: BufRead>(reader: R) -> Result<Baseline> {
+        let mut medians = HashMap::new();
+        for (line_index, line) in input::lines(reader).enumerate() {
+            let line = line?;
+            if let Some((key, median)) = Self::parse_line(&line).map_err(|e| e.at_line(line_index + 1, &line))? {
+                medians.insert(key, median);
+            }
+        }
+        Ok(Baseline { medians })
+    }
+
+    pub fn load(path: &Path) -> Result<Baseline> {
+        Baseline::parse(BufReader::new(File::open(path)?))
+    }
+
+    pub fn save(report: &BenchReport, path: &Path) -> Result<()> {
+        let mut contents = String::new();
+        for (phase, stats) in &report.phases {
+            contents.push_str(&format!("{} {} {}\n", report.day, phase, stats.median.as_nanos()));
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn median(&self, day: u32, phase: &str) -> Option<Duration> {
+        self.medians.get(&(day, phase.to_string())).copied()
+    }
+
+    pub fn change(&self, day: u32, phase: &str, median: Duration) -> Option<f64> {
+        let baseline = self.median(day, phase)?.as_nanos() as f64;
+        if baseline == 0.0 {
+            return None;
+        }
+        Some((median.as_nanos() as f64 - baseline) / baseline * 100.0)
+    }
+}
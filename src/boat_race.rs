@@ -1,31 +1,39 @@
-use std::error::Error;
-use std::result::Result;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::io::BufRead;
 use std::path::Path;
 
-fn parse_line_values(line: &str) -> Result<Vec<u64>, Box<dyn Error>> {
-    line.split(":")
-        .nth(1)
-        .ok_or("Expected a ':' in the line")?
-        .split_whitespace()
-        .map(|s| s.parse::<u64>())
-        .collect::<Result<Vec<u64>, ParseIntError>>()
-        .map_err(|e| e.into())
+use crate::error::{Error, Result};
+use crate::generate::{GenOptions, Rng};
+use crate::input::{self, fields, parse_field};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+
+fn split_label(line: &str) -> Result<(usize, &str)> {
+    let (label, values) = line.split_once(":")
+        .ok_or_else(|| Error::parse(line.len() + 1, "", "Expected a ':' in the line"))?;
+    Ok((label.len() + 1, values))
+}
+
+fn parse_line_values(line: &str) -> Result<Vec<u64>> {
+    let (values_offset, values) = split_label(line)?;
+    fields(values)
+        .into_iter()
+        .map(|(offset, s)| parse_field::<u64>(values_offset + offset, s))
+        .collect::<Result<Vec<u64>>>()
 }
 
-fn parse_line_into_number(line: &str) -> Result<u64, Box<dyn Error>> {
-    let number_str = line.split(":")
-        .nth(1)
-        .ok_or("Expected a ':' in the line")?
+fn parse_line_into_number(line: &str) -> Result<u64> {
+    let (values_offset, values) = split_label(line)?;
+    let number_str = values
         .chars()
         .filter(|c| !c.is_whitespace())
         .collect::<String>();
-    number_str.parse::<u64>().map_err(|e| e.into())
+    let start = fields(values).first().map_or(0, |&(offset, _)| offset);
+    number_str.parse::<u64>()
+        .map_err(|e| Error::parse(values_offset + start + 1, values.trim(),
+            format!("invalid number '{}': {}", number_str, e)))
 }
 
-fn record_hold_interval(total_time: u64, record_distance: u64) -> Result<(u64, u64), Box<dyn Error>> {
+pub fn record_hold_interval(total_time: u64, record_distance: u64) -> Result<(u64, u64)> {
     let t = total_time as f64;
     let d = record_distance as f64;
     let h_min = (t - (t * t - 4.0 * d).sqrt()) / 2.0;
@@ -33,51 +41,144 @@ fn record_hold_interval(total_time: u64, record_distance: u64) -> Result<(u64, u
     if h_min.is_finite() && h_max.is_finite() {
         Ok((h_min.floor() as u64, h_max.ceil() as u64))
     } else {
-        Err(From::from(format!("No solution for total_time: {}, record_distance: {}",
+        Err(Error::unsolvable(format!("No solution for total_time: {}, record_distance: {}",
         total_time, record_distance)))
     }
 } 
 
-pub fn run_day_6<P>(path: P) -> Result<(), Box<dyn Error>> 
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
+fn reference_ways(total_time: u64, record_distance: u64) -> u64 {
+    (0..=total_time)
+        .filter(|&hold| hold * (total_time - hold) > record_distance)
+        .count() as u64
+}
 
-    let time_line = lines.next().ok_or("Expected a line")??;
-    let times = parse_line_values(&time_line)?;
+// The joined race is too long to try every hold time, but the distance
+// grows with the hold time up to half the race, so search for the shortest
+// winning hold with exact integer arithmetic instead.
+fn reference_ways_search(total_time: u64, record_distance: u64) -> u64 {
+    let wins = |hold: u64| hold as u128 * (total_time - hold) as u128 > record_distance as u128;
+    let (mut low, mut high) = (0, total_time / 2);
+    if !wins(high) {
+        return 0;
+    }
+    while low < high {
+        let middle = (low + high) / 2;
+        if wins(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    total_time - 2 * low + 1
+}
 
-    let distance_line = lines.next().ok_or("Expected a line")??;
-    let distances = parse_line_values(&distance_line)?;
+pub struct BoatRace {
+    times: Vec<u64>,
+    distances: Vec<u64>,
+    time: u64,
+    distance: u64,
+}
 
-    println!("Times: {:?}", times);
-    println!("Distances: {:?}", distances);
+impl BoatRace {
+    pub fn races(&self) -> Vec<(u64, u64)> {
+        self.times.iter().cloned().zip(self.distances.iter().cloned()).collect()
+    }
 
-    let num_win_hold_times: Result<Vec<u64>, _> = times.iter()
-        .zip(distances.iter())
-        .map(|(&time, &distance)| {
-            record_hold_interval(time, distance)
-                .map(|(min, max)| max - min - 1)
-        })
-        .collect();
+    pub fn race(&self) -> (u64, u64) {
+        (self.time, self.distance)
+    }
+}
 
-    match num_win_hold_times {
-        Ok(values) => {
-            let product = values.iter().product::<u64>();
-            println!("Number of ways to beat record: {}", product);
-        },
-        Err(e) => {
-            println!("Error: {}", e);
-        }   
+impl Solution for BoatRace {
+    fn parse<R: BufRead>(reader: R) -> Result<BoatRace> {
+        let mut lines = input::lines(reader);
+
+        let time_line = lines.next().ok_or_else(|| Error::MissingSection("Time".to_string()))??;
+        let times = parse_line_values(&time_line).map_err(|e| e.at_line(1, &time_line))?;
+
+        let distance_line = lines.next().ok_or_else(|| Error::MissingSection("Distance".to_string()))??;
+        let distances = parse_line_values(&distance_line).map_err(|e| e.at_line(2, &distance_line))?;
+
+        let time = parse_line_into_number(&time_line).map_err(|e| e.at_line(1, &time_line))?;
+        let distance = parse_line_into_number(&distance_line).map_err(|e| e.at_line(2, &distance_line))?;
+
+        Ok(BoatRace { times, distances, time, distance })
     }
 
-    let time = parse_line_into_number(&time_line)?;
-    let distance = parse_line_into_number(&distance_line)?;
+    fn part_one(&self) -> Result<u64> {
+        let num_win_hold_times = self.times.iter()
+            .zip(self.distances.iter())
+            .map(|(&time, &distance)| {
+                record_hold_interval(time, distance)
+                    .map(|(min, max)| {
+                        crate::trace!(1, "race {} ms, record {} mm: hold {} to {} ms, {} ways",
+                            time, distance, min + 1, max.saturating_sub(1), max.saturating_sub(min + 1));
+                        max.saturating_sub(min + 1)
+                    })
+            })
+            .collect::<Result<Vec<u64>>>()?;
+        num_win_hold_times.iter()
+            .try_fold(1u64, |product, &ways| product.checked_mul(ways))
+            .ok_or_else(|| Error::unsolvable("product of ways to win overflows u64"))
+    }
 
-    let (min, max) = record_hold_interval(time, distance)?;
-    println!("Minimum number of holds to beat long record: {}", max - min - 1);
+    fn part_two(&self) -> Result<u64> {
+        let (min, max) = record_hold_interval(self.time, self.distance)?;
+        crate::trace!(1, "race {} ms, record {} mm: hold {} to {} ms, {} ways",
+            self.time, self.distance, min + 1, max.saturating_sub(1), max.saturating_sub(min + 1));
+        Ok(max.saturating_sub(min + 1))
+    }
+
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        Some(Ok(self.races().iter().map(|&(time, distance)| reference_ways(time, distance)).product()))
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        Some(Ok(reference_ways_search(self.time, self.distance)))
+    }
+
+    fn diagnostics(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("times", format!("{:?}", self.times)),
+            ("distances", format!("{:?}", self.distances)),
+        ]
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[("race <time> <distance>", "hold times that beat the record distance")]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "race" => {
+                let (time, distance) = (argument(args, 0, "time")?, argument(args, 1, "distance")?);
+                let (min, max) = record_hold_interval(time, distance)?;
+                Ok(format!("hold {} to {} ms, {} ways", min + 1, max.saturating_sub(1), max.saturating_sub(min + 1)))
+            }
+            _ => Err(repl::unknown_command(command)),
+        }
+    }
+}
+
+// Part two joins every column into one number, so more than six races
+// would overflow u64.
+pub const MAX_GENERATED_RACES: usize = 6;
+
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    let races: Vec<(usize, usize)> = (0..options.size.clamp(1, MAX_GENERATED_RACES))
+        .map(|_| {
+            let time = rng.range(10..100);
+            (time, rng.range(0..(time * time / 4).min(1000)))
+        })
+        .collect();
+    let times: Vec<String> = races.iter().map(|(time, _)| format!("{:>4}", time)).collect();
+    let distances: Vec<String> = races.iter().map(|(_, distance)| format!("{:>4}", distance)).collect();
+    format!("Time:    {}\nDistance:{}\n", times.join(" "), distances.join(" "))
+}
 
-    Ok(())
+pub fn run_day_6<P>(path: P) -> Result<Answer> 
+where
+    P: AsRef<Path>,
+{
+    Ok(BoatRace::parse_file(path.as_ref())?.solve())
 }
\ No newline at end of file
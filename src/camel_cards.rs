@@ -1,58 +1,88 @@
 use std::collections::HashMap;
-use std::error::Error;
 use std::fmt;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
+use crate::error::{Error, Result};
+use crate::generate::{GenOptions, Rng};
+use crate::input::{self, fields, parse_field};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+
 pub trait CardValue {
-    fn card_value(self) -> u8;
+    fn card_value(self) -> Option<u8>;
 }
 
 impl CardValue for char {
-    fn card_value(self) -> u8 {
+    fn card_value(self) -> Option<u8> {
         match self {
-            '2' => 2,
-            '3' => 3,
-            '4' => 4,
-            '5' => 5,
-            '6' => 6,
-            '7' => 7,
-            '8' => 8,
-            '9' => 9,
-            'T' => 10,
-            'J' => 11,
-            'Q' => 12,
-            'K' => 13,
-            'A' => 14,
-            _ => panic!("Invalid card label"),
+            '2' => Some(2),
+            '3' => Some(3),
+            '4' => Some(4),
+            '5' => Some(5),
+            '6' => Some(6),
+            '7' => Some(7),
+            '8' => Some(8),
+            '9' => Some(9),
+            'T' => Some(10),
+            'J' => Some(11),
+            'Q' => Some(12),
+            'K' => Some(13),
+            'A' => Some(14),
+            _ => None,
         }
     }
 }
 
 pub trait CardValueJoker {
-    fn card_value_joker(self) -> u8;
+    fn card_value_joker(self) -> Option<u8>;
 }
 
 impl CardValueJoker for char {
-    fn card_value_joker(self) -> u8 {
+    fn card_value_joker(self) -> Option<u8> {
         match self {
-            'J' => 1,
-            '2' => 2,
-            '3' => 3,
-            '4' => 4,
-            '5' => 5,
-            '6' => 6,
-            '7' => 7,
-            '8' => 8,
-            '9' => 9,
-            'T' => 10,
-            'Q' => 11,
-            'K' => 12,
-            'A' => 13,
-            _ => panic!("Invalid card label"),
+            'J' => Some(1),
+            '2' => Some(2),
+            '3' => Some(3),
+            '4' => Some(4),
+            '5' => Some(5),
+            '6' => Some(6),
+            '7' => Some(7),
+            '8' => Some(8),
+            '9' => Some(9),
+            'T' => Some(10),
+            'Q' => Some(11),
+            'K' => Some(12),
+            'A' => Some(13),
+            _ => None,
+        }
+    }
+}
+
+fn parse_hand(hand_str: &str) -> Result<([char; 5], u64)> {
+    check_card_labels(hand_str)?;
+    let parts = fields(hand_str);
+    if parts.len() != 2 {
+        return Err(Error::parse(1, hand_str.trim(), format!("expected '<cards> <bid>', got '{}'", hand_str.trim())));
+    }
+    let (cards_offset, cards_str) = parts[0];
+    let (bid_offset, bid_str) = parts[1];
+    let cards: [char; 5] = cards_str.chars().collect::<Vec<_>>().try_into()
+        .map_err(|_| Error::parse(cards_offset + 1, cards_str,
+            format!("Cards string must have exactly 5 characters, got '{}'", cards_str)))?;
+    let bid: u64 = parse_field(bid_offset, bid_str)?;
+    Ok((cards, bid))
+}
+
+fn check_card_labels(line: &str) -> Result<()> {
+    if let Some(&(offset, cards)) = fields(line).first() {
+        for (index, card) in cards.char_indices() {
+            if card.card_value().is_none() {
+                return Err(Error::parse(offset + index + 1, &cards[index..index + card.len_utf8()], format!("Invalid card label '{}'", card)));
+            }
         }
     }
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -92,16 +122,16 @@ impl Ord for HandRank {
 
 impl PartialEq for HandRank {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (HandRank::FiveOfAKind(_), HandRank::FiveOfAKind(_)) => true,
-            (HandRank::FourOfAKind(_), HandRank::FourOfAKind(_)) => true,
-            (HandRank::FullHouse(_, _), HandRank::FullHouse(_, _)) => true,
-            (HandRank::ThreeOfAKind(_), HandRank::ThreeOfAKind(_)) => true,
-            (HandRank::TwoPairs(_, _), HandRank::TwoPairs(_, _)) => true,
-            (HandRank::OnePair(_), HandRank::OnePair(_)) => true,
-            (HandRank::HighCard, HandRank::HighCard) => true,
-            _ => false,
-        }
+        matches!(
+            (self, other),
+            (HandRank::FiveOfAKind(_), HandRank::FiveOfAKind(_))
+                | (HandRank::FourOfAKind(_), HandRank::FourOfAKind(_))
+                | (HandRank::FullHouse(_, _), HandRank::FullHouse(_, _))
+                | (HandRank::ThreeOfAKind(_), HandRank::ThreeOfAKind(_))
+                | (HandRank::TwoPairs(_, _), HandRank::TwoPairs(_, _))
+                | (HandRank::OnePair(_), HandRank::OnePair(_))
+                | (HandRank::HighCard, HandRank::HighCard)
+        )
     }
 }
 
@@ -123,8 +153,8 @@ impl Ord for Hand {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match self.rank.cmp(&other.rank) {
             std::cmp::Ordering::Equal => {
-                let self_card_values: Vec<u8> = self.cards.iter().map(|&card| card.card_value()).collect();
-                let other_card_values: Vec<u8> = other.cards.iter().map(|&card| card.card_value()).collect();
+                let self_card_values: Vec<Option<u8>> = self.cards.iter().map(|&card| card.card_value()).collect();
+                let other_card_values: Vec<Option<u8>> = other.cards.iter().map(|&card| card.card_value()).collect();
                 self_card_values.cmp(&other_card_values)
             },
             ordering => ordering,
@@ -141,16 +171,16 @@ impl PartialEq for Hand {
 impl Eq for Hand {}
 
 impl Hand {
-    pub fn from_string(hand_str: &str) -> Result<Hand, Box<dyn Error>> {
-        let parts: Vec<&str> = hand_str.split_whitespace().collect();
-        if parts.len() != 2 {
-            return Err("Input string must have exactly two parts".into());
-        }
-        if parts[0].len() != 5 {
-            return Err("Cards string must have exactly 5 characters".into());
-        }
-        let cards: [char; 5] = parts[0].chars().collect::<Vec<_>>()[..5].try_into()?;
-        let bid: u64 = parts[1].parse()?;
+    pub fn cards(&self) -> &[char; 5] {
+        &self.cards
+    }
+
+    pub fn bid(&self) -> u64 {
+        self.bid
+    }
+
+    pub fn from_string(hand_str: &str) -> Result<Hand> {
+        let (cards, bid) = parse_hand(hand_str)?;
 
         let mut card_counts = HashMap::new();
         for &card in &cards {
@@ -252,8 +282,8 @@ impl Ord for HandJoker {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match self.rank.cmp(&other.rank) {
             std::cmp::Ordering::Equal => {
-                let self_card_values: Vec<u8> = self.cards.iter().map(|&card| card.card_value_joker()).collect();
-                let other_card_values: Vec<u8> = other.cards.iter().map(|&card| card.card_value_joker()).collect();
+                let self_card_values: Vec<Option<u8>> = self.cards.iter().map(|&card| card.card_value_joker()).collect();
+                let other_card_values: Vec<Option<u8>> = other.cards.iter().map(|&card| card.card_value_joker()).collect();
                 self_card_values.cmp(&other_card_values)
             },
             ordering => ordering,
@@ -270,16 +300,16 @@ impl PartialEq for HandJoker {
 impl Eq for HandJoker {}
 
 impl HandJoker {
-    pub fn from_string(hand_str: &str) -> Result<HandJoker, Box<dyn Error>> {
-        let parts: Vec<&str> = hand_str.split_whitespace().collect();
-        if parts.len() != 2 {
-            return Err("Input string must have exactly two parts".into());
-        }
-        if parts[0].len() != 5 {
-            return Err("Cards string must have exactly 5 characters".into());
-        }
-        let cards: [char; 5] = parts[0].chars().collect::<Vec<_>>()[..5].try_into()?;
-        let bid: u64 = parts[1].parse()?;
+    pub fn cards(&self) -> &[char; 5] {
+        &self.cards
+    }
+
+    pub fn bid(&self) -> u64 {
+        self.bid
+    }
+
+    pub fn from_string(hand_str: &str) -> Result<HandJoker> {
+        let (cards, bid) = parse_hand(hand_str)?;
 
         let mut card_counts = HashMap::new();
         for &card in &cards {
@@ -391,39 +421,151 @@ impl HandJoker {
     }
 }
 
-pub fn run_day_7<P>(path: P) -> Result<(), Box<dyn Error>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let lines = reader.lines();
-
-    let mut hands = lines
-        .map(|line| Hand::from_string(&line.unwrap()))
-        .collect::<Result<Vec<Hand>, Box<dyn Error>>>()?;
+fn reference_strength(cards: &[char]) -> u8 {
+    let mut counts: Vec<usize> = cards.iter()
+        .map(|card| cards.iter().filter(|other| *other == card).count())
+        .collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    match counts.as_slice() {
+        [5, ..] => 7,
+        [4, ..] => 6,
+        [3, 3, 3, 2, 2] => 5,
+        [3, ..] => 4,
+        [2, 2, 2, 2, 1] => 3,
+        [2, ..] => 2,
+        _ => 1,
+    }
+}
 
-    hands.sort_unstable();
+// Turning every joker into the same label is always at least as good as
+// splitting them, so trying each label covers every best hand.
+fn reference_joker_strength(cards: &[char]) -> u8 {
+    "23456789TQKA"
+        .chars()
+        .map(|label| {
+            let replaced: Vec<char> = cards.iter().map(|&card| if card == 'J' { label } else { card }).collect();
+            reference_strength(&replaced)
+        })
+        .max()
+        .unwrap_or(1)
+}
 
-    let total_winnings: u64 = hands.iter()
+// Sums each bid times its rank, given the bids from weakest to strongest hand.
+fn total_winnings<I>(ranked_bids: I) -> Result<u64>
+where I: Iterator<Item = u64> {
+    ranked_bids
         .enumerate()
-        .map(|(index, hand)| hand.bid * (index + 1) as u64)
-        .sum();
+        .try_fold(0u64, |total, (index, bid)| {
+            bid.checked_mul(index as u64 + 1).and_then(|winnings| total.checked_add(winnings))
+        })
+        .ok_or_else(|| Error::unsolvable("total winnings overflow u64"))
+}
+
+fn reference_winnings<K>(hands: &[Hand], key: K) -> Result<u64>
+where K: Fn(&Hand) -> (u8, Vec<Option<u8>>) {
+    let mut ranked: Vec<&Hand> = hands.iter().collect();
+    ranked.sort_by_key(|hand| key(hand));
+    total_winnings(ranked.iter().map(|hand| hand.bid))
+}
+
+pub struct CamelCards {
+    hands: Vec<Hand>,
+}
 
-    println!("Total winnings: {}", total_winnings);
+impl CamelCards {
+    pub fn hands(&self) -> &[Hand] {
+        &self.hands
+    }
+}
 
-    let mut hands_joker = hands.iter()
-        .map(|hand| HandJoker::from_string(&hand.to_string()))
-        .collect::<Result<Vec<HandJoker>, Box<dyn Error>>>()?;
+impl Solution for CamelCards {
+    fn parse<R: BufRead>(reader: R) -> Result<CamelCards> {
+        let lines = input::lines(reader);
 
-    hands_joker.sort_unstable();
+        let hands = lines
+            .enumerate()
+            .map(|(line_index, line)| {
+                let line = line?;
+                Hand::from_string(&line)
+                    .map_err(|e| e.at_line(line_index + 1, &line))
+            })
+            .collect::<Result<Vec<Hand>>>()?;
 
-    let total_winnings_joker: u64 = hands_joker.iter()
-        .enumerate()
-        .map(|(index, hand)| hand.bid * (index + 1) as u64)
-        .sum();
+        Ok(CamelCards { hands })
+    }
 
-    println!("Total winnings with joker: {}", total_winnings_joker);
+    fn part_one(&self) -> Result<u64> {
+        let mut hands: Vec<&Hand> = self.hands.iter().collect();
 
-    Ok(())
+        hands.sort_unstable();
+
+        total_winnings(hands.iter()
+            .enumerate()
+            .map(|(index, hand)| {
+                crate::trace!(1, "hand {}: {:?}, rank {}", hand, hand.rank, index + 1);
+                hand.bid
+            }))
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        let mut hands_joker = self.hands.iter()
+            .map(|hand| HandJoker::from_string(&hand.to_string()))
+            .collect::<Result<Vec<HandJoker>>>()?;
+
+        hands_joker.sort_unstable();
+
+        total_winnings(hands_joker.iter()
+            .enumerate()
+            .map(|(index, hand)| {
+                crate::trace!(1, "hand {} {}: {:?} with jokers, rank {}",
+                    hand.cards.iter().collect::<String>(), hand.bid, hand.rank, index + 1);
+                hand.bid
+            }))
+    }
+
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        Some(reference_winnings(&self.hands, |hand| {
+            (reference_strength(&hand.cards), hand.cards.iter().map(|&card| card.card_value()).collect())
+        }))
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        Some(reference_winnings(&self.hands, |hand| {
+            (reference_joker_strength(&hand.cards), hand.cards.iter().map(|&card| card.card_value_joker()).collect())
+        }))
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[("hand <cards>", "rank of a hand with and without jokers")]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "hand" => {
+                let cards: String = argument(args, 0, "cards")?;
+                let line = format!("{} 0", cards);
+                let hand = Hand::from_string(&line)?;
+                let hand_joker = HandJoker::from_string(&line)?;
+                Ok(format!("{}: {:?}, with jokers {:?}", cards, hand.rank, hand_joker.rank))
+            }
+            _ => Err(repl::unknown_command(command)),
+        }
+    }
+}
+
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    const LABELS: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
+    let mut output = String::new();
+    for _ in 0..options.size {
+        let cards: String = (0..5).map(|_| *rng.choose(&LABELS)).collect();
+        output.push_str(&format!("{} {}\n", cards, rng.range(1..1001)));
+    }
+    output
+}
+
+pub fn run_day_7<P>(path: P) -> Result<Answer>
+where
+    P: AsRef<Path>,
+{
+    Ok(CamelCards::parse_file(path.as_ref())?.solve())
 }
\ No newline at end of file
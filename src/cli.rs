@@ -0,0 +1,454 @@
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::crosscheck::CrosscheckOptions;
+use crate::generate::GenOptions;
+use crate::output::Format;
+use crate::scaffold;
+use crate::trace;
+use crate::trebuchet::MissingDigitPolicy;
+
+pub const USAGE: &str = "\
+Usage: advent_of_code_2023 <command> [options]
+
+Commands:
+  run <day> [input]    Solve one day (input defaults to ./input_<day> or ./input_<day>_short, - reads stdin)
+  all [dir]            Solve every registered day with inputs discovered in dir (default: .)
+  check [dir]          Like all, but compare against the answers file in dir
+  bench <day> [input]  Time parse and both parts over repeated runs
+  watch <day> [input]  Re-run a day whenever its input or answers file changes
+  repl <day> [input]   Load a day once and answer queries about it interactively
+  new-day <day> <name> Generate a module, registry entry, test file and example input for a new day
+  gen <day>            Print a random valid input for a day
+  crosscheck [day] [input]
+                       Compare optimized parts with reference solvers on generated inputs, or on input if given
+  calibrate [input]    Sum day 1 calibration values, optionally with a custom --vocabulary
+  list                 List the registered days
+  help                 Show this message
+
+Options:
+  -i, --input <path>   Input file for run, input directory for all
+  -p, --part <1|2>     Only solve one part (run and watch only)
+  -t, --time           Print parse, part one and part two timings
+  -f, --format <fmt>   Output format: text (default), json or csv
+  -a, --answers <path> Answers file for check and watch (default: <dir>/answers)
+  -n, --iterations <n> Measured runs per phase for bench (default: 10)
+      --warmup <n>     Unmeasured runs per phase for bench (default: 2)
+      --save-baseline <path>
+                       Save bench medians to a baseline file
+      --baseline <path>
+                       Compare bench medians against a saved baseline
+      --interval <ms>  Polling interval for watch (default: 500)
+  -j, --jobs <n>       Days solved in parallel by all and check (default: available cores)
+      --trace <level>  Explain intermediate results on stderr: 1 per item, 2 per step
+      --seed <n>       Random seed for gen and the first crosscheck input (default: 2023)
+      --size <n>       Lines, games, cards, hands, seed pairs or ghosts for gen (default: 100, crosscheck: 4)
+      --width <n>      Shape of each item for gen, e.g. tokens per line or entries per map (default: 10, crosscheck: 4)
+      --runs <n>       Generated inputs per day for crosscheck (default: 10)
+      --vocabulary <path>
+                       Lines of '<word> <digit>' for calibrate, replacing one to nine
+      --missing <policy>
+                       Lines without a digit for calibrate: skip (default), zero or error
+      --table          Print the first and last token of every line for calibrate
+  -h, --help           Show this message
+
+For compatibility, `advent_of_code_2023 <day> <input>` is the same as `run <day> <input>`.";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run { day: u32, input: Option<PathBuf>, part: Option<u8>, time: bool, format: Format, trace: u8 },
+    All { dir: PathBuf, time: bool, format: Format, jobs: Option<usize>, trace: u8 },
+    Check { dir: PathBuf, answers: Option<PathBuf>, jobs: Option<usize>, trace: u8 },
+    Bench {
+        day: u32,
+        input: Option<PathBuf>,
+        iterations: usize,
+        warmup: usize,
+        save_baseline: Option<PathBuf>,
+        baseline: Option<PathBuf>,
+    },
+    Watch {
+        day: u32,
+        input: Option<PathBuf>,
+        part: Option<u8>,
+        answers: Option<PathBuf>,
+        interval: Duration,
+        trace: u8,
+    },
+    Repl { day: u32, input: Option<PathBuf> },
+    NewDay { day: u32, name: String },
+    Gen { day: u32, seed: u64, size: usize, width: usize },
+    Calibrate { input: Option<PathBuf>, vocabulary: Option<PathBuf>, missing: MissingDigitPolicy, table: bool },
+    Crosscheck { day: Option<u32>, input: Option<PathBuf>, seed: u64, runs: usize, size: usize, width: usize },
+    List,
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+fn usage_error<T>(message: String) -> Result<T, UsageError> {
+    Err(UsageError(message))
+}
+
+fn parse_day(value: &str) -> Result<u32, UsageError> {
+    value.parse::<u32>()
+        .or_else(|_| usage_error(format!("Invalid day '{}', expected a number", value)))
+}
+
+fn parse_part(value: &str) -> Result<u8, UsageError> {
+    match value {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => usage_error(format!("Invalid part '{}', expected 1 or 2", value)),
+    }
+}
+
+fn parse_trace(value: &str) -> Result<u8, UsageError> {
+    match value.parse::<u8>() {
+        Ok(level) if level <= trace::MAX_LEVEL => Ok(level),
+        _ => usage_error(format!("Invalid trace level '{}', expected 0 to {}", value, trace::MAX_LEVEL)),
+    }
+}
+
+fn parse_count(name: &str, value: &str) -> Result<usize, UsageError> {
+    value.parse::<usize>()
+        .or_else(|_| usage_error(format!("Invalid value '{}' for {}, expected a number", value, name)))
+}
+
+struct Options {
+    positionals: Vec<String>,
+    input: Option<PathBuf>,
+    part: Option<u8>,
+    time: bool,
+    format: Format,
+    answers: Option<PathBuf>,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    save_baseline: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    interval: Option<usize>,
+    jobs: Option<usize>,
+    trace: u8,
+    seed: Option<usize>,
+    size: Option<usize>,
+    width: Option<usize>,
+    runs: Option<usize>,
+    vocabulary: Option<PathBuf>,
+    missing: MissingDigitPolicy,
+    table: bool,
+    help: bool,
+    given: Vec<&'static str>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, UsageError> {
+    let mut options = Options {
+        positionals: Vec::new(),
+        input: None,
+        part: None,
+        time: false,
+        format: Format::Text,
+        answers: None,
+        iterations: None,
+        warmup: None,
+        save_baseline: None,
+        baseline: None,
+        interval: None,
+        jobs: None,
+        trace: 0,
+        seed: None,
+        size: None,
+        width: None,
+        runs: None,
+        vocabulary: None,
+        missing: MissingDigitPolicy::Skip,
+        table: false,
+        help: false,
+        given: Vec::new(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| UsageError(format!("Missing value for {}", name)))
+        };
+        let name = match arg.as_str() {
+            "-i" | "--input" => { options.input = Some(PathBuf::from(value(arg)?)); "--input" }
+            "-p" | "--part" => { options.part = Some(parse_part(&value(arg)?)?); "--part" }
+            "-t" | "--time" => { options.time = true; "--time" }
+            "-f" | "--format" => { options.format = value(arg)?.parse().map_err(UsageError)?; "--format" }
+            "-a" | "--answers" => { options.answers = Some(PathBuf::from(value(arg)?)); "--answers" }
+            "-n" | "--iterations" => { options.iterations = Some(parse_count(arg, &value(arg)?)?); "--iterations" }
+            "--warmup" => { options.warmup = Some(parse_count(arg, &value(arg)?)?); "--warmup" }
+            "--save-baseline" => { options.save_baseline = Some(PathBuf::from(value(arg)?)); "--save-baseline" }
+            "--baseline" => { options.baseline = Some(PathBuf::from(value(arg)?)); "--baseline" }
+            "--interval" => { options.interval = Some(parse_count(arg, &value(arg)?)?); "--interval" }
+            "-j" | "--jobs" => { options.jobs = Some(parse_count(arg, &value(arg)?)?); "--jobs" }
+            "--trace" => { options.trace = parse_trace(&value(arg)?)?; "--trace" }
+            "--seed" => { options.seed = Some(parse_count(arg, &value(arg)?)?); "--seed" }
+            "--size" => { options.size = Some(parse_count(arg, &value(arg)?)?); "--size" }
+            "--width" => { options.width = Some(parse_count(arg, &value(arg)?)?); "--width" }
+            "--runs" => { options.runs = Some(parse_count(arg, &value(arg)?)?); "--runs" }
+            "--vocabulary" => { options.vocabulary = Some(PathBuf::from(value(arg)?)); "--vocabulary" }
+            "--missing" => { options.missing = value(arg)?.parse().map_err(UsageError)?; "--missing" }
+            "--table" => { options.table = true; "--table" }
+            "-h" | "--help" => { options.help = true; "--help" }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return usage_error(format!("Unknown option '{}'", flag));
+            }
+            _ => {
+                options.positionals.push(arg.clone());
+                continue;
+            }
+        };
+        options.given.push(name);
+    }
+    Ok(options)
+}
+
+// Options each command uses. Any other option is rejected instead of being
+// silently ignored.
+const COMMAND_OPTIONS: &[(&str, &[&str])] = &[
+    ("run", &["--input", "--part", "--time", "--format", "--trace"]),
+    ("all", &["--input", "--time", "--format", "--jobs", "--trace"]),
+    ("check", &["--input", "--format", "--answers", "--jobs", "--trace"]),
+    ("bench", &["--input", "--iterations", "--warmup", "--save-baseline", "--baseline"]),
+    ("watch", &["--input", "--part", "--format", "--answers", "--interval", "--trace"]),
+    ("repl", &["--input"]),
+    ("new-day", &[]),
+    ("gen", &["--seed", "--size", "--width"]),
+    ("crosscheck", &["--input", "--seed", "--size", "--width", "--runs"]),
+    ("calibrate", &["--input", "--vocabulary", "--missing", "--table"]),
+    ("list", &[]),
+];
+
+fn expect_options(command: &str, options: &Options) -> Result<(), UsageError> {
+    let accepts = |command: &str, option: &str| COMMAND_OPTIONS.iter()
+        .any(|&(name, accepted)| name == command && accepted.contains(&option));
+    let Some(option) = options.given.iter().find(|&&option| option != "--help" && !accepts(command, option)) else {
+        return Ok(());
+    };
+    let supporting: Vec<&str> = COMMAND_OPTIONS.iter()
+        .map(|&(name, _)| name)
+        .filter(|name| accepts(name, option))
+        .collect();
+    match supporting.split_last() {
+        Some((last, [])) => usage_error(format!("{} is only supported by {}", option, last)),
+        Some((last, rest)) if rest.len() < 4 => {
+            usage_error(format!("{} is only supported by {} and {}", option, rest.join(", "), last))
+        }
+        _ => usage_error(format!("{} is not supported by {}", option, command)),
+    }
+}
+
+fn expect_positionals(command: &str, positionals: &[String], max: usize) -> Result<(), UsageError> {
+    if positionals.len() > max {
+        return usage_error(format!("Unexpected argument '{}' for {}", positionals[max], command));
+    }
+    Ok(())
+}
+
+fn parse_day_input(command: &str, options: &Options) -> Result<(u32, Option<PathBuf>), UsageError> {
+    expect_positionals(command, &options.positionals, 2)?;
+    let day = match options.positionals.first() {
+        Some(day) => parse_day(day)?,
+        None => return usage_error(format!("Missing day for {}", command)),
+    };
+    let input = match (&options.input, options.positionals.get(1)) {
+        (Some(_), Some(_)) => return usage_error("Input given both as argument and --input".to_string()),
+        (Some(input), None) => Some(input.clone()),
+        (None, positional) => positional.map(PathBuf::from),
+    };
+    Ok((day, input))
+}
+
+fn parse_run(options: Options) -> Result<Command, UsageError> {
+    let (day, input) = parse_day_input("run", &options)?;
+    Ok(Command::Run {
+        day,
+        input,
+        part: options.part,
+        time: options.time,
+        format: options.format,
+        trace: options.trace,
+    })
+}
+
+fn parse_bench(options: Options) -> Result<Command, UsageError> {
+    let (day, input) = parse_day_input("bench", &options)?;
+    let iterations = options.iterations.unwrap_or(10);
+    if iterations == 0 {
+        return usage_error("--iterations must be at least 1".to_string());
+    }
+    Ok(Command::Bench {
+        day,
+        input,
+        iterations,
+        warmup: options.warmup.unwrap_or(2),
+        save_baseline: options.save_baseline,
+        baseline: options.baseline,
+    })
+}
+
+fn parse_watch(options: Options) -> Result<Command, UsageError> {
+    let (day, input) = parse_day_input("watch", &options)?;
+    if options.format != Format::Text {
+        return usage_error("watch only supports text output".to_string());
+    }
+    let interval = options.interval.unwrap_or(500);
+    if interval == 0 {
+        return usage_error("--interval must be at least 1".to_string());
+    }
+    Ok(Command::Watch {
+        day,
+        input,
+        part: options.part,
+        answers: options.answers,
+        interval: Duration::from_millis(interval as u64),
+        trace: options.trace,
+    })
+}
+
+fn parse_repl(options: Options) -> Result<Command, UsageError> {
+    let (day, input) = parse_day_input("repl", &options)?;
+    if input.as_deref() == Some(Path::new("-")) {
+        return usage_error("repl reads queries from stdin, pass an input file".to_string());
+    }
+    Ok(Command::Repl { day, input })
+}
+
+fn parse_new_day(options: Options) -> Result<Command, UsageError> {
+    expect_positionals("new-day", &options.positionals, 2)?;
+    let (day, name) = match options.positionals.as_slice() {
+        [day, name] => (parse_day(day)?, name.clone()),
+        _ => return usage_error("new-day needs a day number and a module name".to_string()),
+    };
+    if !scaffold::is_valid_name(&name) {
+        return usage_error(format!("Invalid module name '{}', expected snake_case", name));
+    }
+    Ok(Command::NewDay { day, name })
+}
+
+fn parse_gen(options: Options) -> Result<Command, UsageError> {
+    expect_positionals("gen", &options.positionals, 1)?;
+    let day = match options.positionals.first() {
+        Some(day) => parse_day(day)?,
+        None => return usage_error("Missing day for gen".to_string()),
+    };
+    let defaults = GenOptions::default();
+    Ok(Command::Gen {
+        day,
+        seed: options.seed.map_or(2023, |seed| seed as u64),
+        size: options.size.unwrap_or(defaults.size),
+        width: options.width.unwrap_or(defaults.width),
+    })
+}
+
+fn parse_crosscheck(options: Options) -> Result<Command, UsageError> {
+    let (day, input) = if options.positionals.is_empty() {
+        if options.input.is_some() {
+            return usage_error("crosscheck needs a day to check an input".to_string());
+        }
+        (None, None)
+    } else {
+        let (day, input) = parse_day_input("crosscheck", &options)?;
+        (Some(day), input)
+    };
+    let defaults = CrosscheckOptions::default();
+    let runs = options.runs.unwrap_or(defaults.runs);
+    if runs == 0 {
+        return usage_error("--runs must be at least 1".to_string());
+    }
+    Ok(Command::Crosscheck {
+        day,
+        input,
+        seed: options.seed.map_or(defaults.seed, |seed| seed as u64),
+        runs,
+        size: options.size.unwrap_or(defaults.generate.size),
+        width: options.width.unwrap_or(defaults.generate.width),
+    })
+}
+
+fn parse_calibrate(options: Options) -> Result<Command, UsageError> {
+    expect_positionals("calibrate", &options.positionals, 1)?;
+    let input = match (&options.input, options.positionals.first()) {
+        (Some(_), Some(_)) => return usage_error("Input given both as argument and --input".to_string()),
+        (Some(input), None) => Some(input.clone()),
+        (None, positional) => positional.map(PathBuf::from),
+    };
+    Ok(Command::Calibrate { input, vocabulary: options.vocabulary, missing: options.missing, table: options.table })
+}
+
+fn parse_dir(command: &str, options: &Options) -> Result<PathBuf, UsageError> {
+    expect_positionals(command, &options.positionals, 1)?;
+    if options.jobs == Some(0) {
+        return usage_error("--jobs must be at least 1".to_string());
+    }
+    match (&options.input, options.positionals.first()) {
+        (Some(_), Some(_)) => usage_error("Directory given both as argument and --input".to_string()),
+        (Some(dir), None) => Ok(dir.clone()),
+        (None, positional) => Ok(PathBuf::from(positional.map_or(".", String::as_str))),
+    }
+}
+
+fn parse_all(options: Options) -> Result<Command, UsageError> {
+    let dir = parse_dir("all", &options)?;
+    Ok(Command::All { dir, time: options.time, format: options.format, jobs: options.jobs, trace: options.trace })
+}
+
+fn parse_check(options: Options) -> Result<Command, UsageError> {
+    let dir = parse_dir("check", &options)?;
+    if options.format != Format::Text {
+        return usage_error("check only supports text output".to_string());
+    }
+    Ok(Command::Check { dir, answers: options.answers, jobs: options.jobs, trace: options.trace })
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, UsageError> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return usage_error("Missing command".to_string()),
+    };
+    if matches!(command, "help" | "-h" | "--help") {
+        return Ok(Command::Help);
+    }
+    let options = parse_options(rest)?;
+    if options.help {
+        return Ok(Command::Help);
+    }
+    let numeric = command.chars().all(|c| c.is_ascii_digit());
+    expect_options(if numeric { "run" } else { command }, &options)?;
+    match command {
+        "run" => parse_run(options),
+        "all" => parse_all(options),
+        "check" => parse_check(options),
+        "bench" => parse_bench(options),
+        "watch" => parse_watch(options),
+        "repl" => parse_repl(options),
+        "new-day" => parse_new_day(options),
+        "gen" => parse_gen(options),
+        "crosscheck" => parse_crosscheck(options),
+        "calibrate" => parse_calibrate(options),
+        "list" => {
+            expect_positionals("list", &options.positionals, 0)?;
+            Ok(Command::List)
+        }
+        _ if numeric => {
+            let mut options = options;
+            options.positionals.insert(0, command.to_string());
+            parse_run(options)
+        }
+        _ => usage_error(format!("Unknown command '{}'", command)),
+    }
+}
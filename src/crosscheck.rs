@@ -0,0 +1,113 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::error::{Error, Result};
+use crate::generate::{GenOptions, Rng};
+use crate::registry::Day;
+use crate::runner::panic_message;
+use crate::solution::Solution;
+
+pub struct CrosscheckOptions {
+    pub seed: u64,
+    pub runs: usize,
+    pub generate: GenOptions,
+}
+
+// Small inputs keep the reference solvers fast, e.g. day 8 walks every
+// step of the least common multiple.
+impl Default for CrosscheckOptions {
+    fn default() -> CrosscheckOptions {
+        CrosscheckOptions { seed: 2023, runs: 10, generate: GenOptions { size: 4, width: 4 } }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceOutcome {
+    Missing,
+    // The reference gave up, e.g. because the input is too large to solve
+    // the slow way. This is not a disagreement.
+    Skipped(String),
+    Solved(std::result::Result<u64, String>),
+}
+
+pub struct PartCrosscheck {
+    pub part: u8,
+    pub optimized: std::result::Result<u64, String>,
+    pub reference: ReferenceOutcome,
+}
+
+impl PartCrosscheck {
+    pub fn agrees(&self) -> bool {
+        match &self.reference {
+            ReferenceOutcome::Solved(reference) => *reference == self.optimized,
+            ReferenceOutcome::Missing | ReferenceOutcome::Skipped(_) => true,
+        }
+    }
+}
+
+pub struct CrosscheckReport {
+    pub day: u32,
+    pub name: &'static str,
+    pub source: String,
+    pub parts: Vec<PartCrosscheck>,
+}
+
+impl CrosscheckReport {
+    pub fn disagreements(&self) -> impl Iterator<Item = &PartCrosscheck> {
+        self.parts.iter().filter(|part| !part.agrees())
+    }
+}
+
+fn guarded<F>(solve: F) -> std::result::Result<u64, String>
+where F: FnOnce() -> Result<u64> {
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(outcome) => outcome.map_err(|e| e.to_string()),
+        Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+    }
+}
+
+fn guarded_reference<F>(solve: F) -> ReferenceOutcome
+where F: FnOnce() -> Option<Result<u64>> {
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(None) => ReferenceOutcome::Missing,
+        Ok(Some(Err(Error::Skipped(message)))) => ReferenceOutcome::Skipped(message),
+        Ok(Some(outcome)) => ReferenceOutcome::Solved(outcome.map_err(|e| e.to_string())),
+        Err(payload) => ReferenceOutcome::Solved(Err(format!("panicked: {}", panic_message(payload.as_ref())))),
+    }
+}
+
+pub fn crosscheck_solution(solution: &dyn Solution) -> Vec<PartCrosscheck> {
+    vec![
+        PartCrosscheck {
+            part: 1,
+            optimized: guarded(|| solution.part_one()),
+            reference: guarded_reference(|| solution.reference_part_one()),
+        },
+        PartCrosscheck {
+            part: 2,
+            optimized: guarded(|| solution.part_two()),
+            reference: guarded_reference(|| solution.reference_part_two()),
+        },
+    ]
+}
+
+pub fn crosscheck_input(day: &Day, source: &str, input: &str) -> Result<CrosscheckReport> {
+    let solution = (day.parse)(&mut input.as_bytes())?;
+    Ok(CrosscheckReport {
+        day: day.number,
+        name: day.name,
+        source: source.to_string(),
+        parts: crosscheck_solution(solution.as_ref()),
+    })
+}
+
+pub fn crosscheck_generated(day: &Day, options: &CrosscheckOptions) -> Result<Vec<CrosscheckReport>> {
+    let generate = day.generate
+        .ok_or_else(|| Error::unsolvable(format!("day {} has no input generator", day.number)))?;
+    (0..options.runs as u64)
+        .map(|run| {
+            let seed = options.seed.wrapping_add(run);
+            let input = generate(&mut Rng::new(seed), &options.generate);
+            crosscheck_input(day, &format!("seed {}", seed), &input)
+        })
+        .collect()
+}
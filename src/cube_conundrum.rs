@@ -1,67 +1,118 @@
-use std::error::Error;
-use std::io::{BufRead, BufReader};
-use std::fs::File;
+use std::io::BufRead;
 use std::path::Path;
 
-enum Color {
+use crate::error::{Error, Result};
+use crate::generate::{GenOptions, Rng};
+use crate::input::{self, fields, parse_field, split_fields};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
     Red,
     Green,
     Blue,
 }
 
-struct CubeSet {
+pub struct CubeSet {
     cubes: Vec<(usize, Color)>
 }
 
 impl CubeSet {
-    fn new() -> CubeSet {
+    pub fn new() -> CubeSet {
         CubeSet { cubes: Vec::new() }
     }
 
-    fn from_string(input: &str) -> Result<CubeSet, Box<dyn std::error::Error>> {
+    pub fn from_string(input: &str) -> Result<CubeSet> {
         let mut cube_set = CubeSet::new();
-        for cube_string in input.split(",") {
-            let mut cube_info = cube_string.split_whitespace();
-            let count = cube_info.next().unwrap().parse::<usize>().unwrap();
-            let color = match cube_info.next().unwrap() {
+        for (offset, cube_string) in split_fields(input, ',') {
+            let cube_info = fields(cube_string);
+            let (count, color) = match cube_info.as_slice() {
+                [count, color] => (count, color),
+                _ => {
+                    let start = cube_info.first().map_or(0, |&(start, _)| start);
+                    return Err(Error::parse(offset + start + 1, cube_string.trim(),
+                        format!("expected '<count> <color>', got '{}'", cube_string.trim())));
+                }
+            };
+            let count = parse_field::<usize>(offset + count.0, count.1)?;
+            let color = match color.1 {
                 "red" => Color::Red,
                 "green" => Color::Green,
                 "blue" => Color::Blue,
-                _ => panic!("Invalid color"),
+                _ => return Err(Error::parse(offset + color.0 + 1, color.1, format!("invalid color '{}'", color.1))),
             };
-            cube_set.cubes.push((count, color));
+            cube_set.add_cubes(count, color);
         }
         Ok(cube_set)
     }
-    
+
+    pub fn add_cubes(&mut self, count: usize, color: Color) {
+        self.cubes.push((count, color));
+    }
+
+    pub fn cubes(&self) -> &[(usize, Color)] {
+        &self.cubes
+    }
+
+    pub fn count(&self, color: Color) -> usize {
+        self.cubes
+            .iter()
+            .filter(|(_, cube_color)| *cube_color == color)
+            .map(|(count, _)| count)
+            .sum()
+    }
 }
 
-struct CubeGame {
+impl Default for CubeSet {
+    fn default() -> CubeSet {
+        CubeSet::new()
+    }
+}
+
+pub struct CubeGame {
     game_index: usize,
     cube_sets: Vec<CubeSet>,
 }
 
 impl CubeGame {
-    fn new(game_index: usize) -> CubeGame {
+    pub fn new(game_index: usize) -> CubeGame {
         CubeGame {
             game_index,
             cube_sets: Vec::new(),
         }
     }
     
-    fn from_string(input: &str) -> Result<CubeGame, Box<dyn std::error::Error>> {
-        let parts: Vec<&str> = input.split(":").collect();
-        let game_index = parts[0][5..].trim().parse().unwrap();
+    pub fn from_string(input: &str) -> Result<CubeGame> {
+        let (header, sets) = input.split_once(':')
+            .ok_or_else(|| Error::parse(input.len() + 1, "", "expected ':' after the game header"))?;
+        let game_index = match fields(header).as_slice() {
+            [(_, "Game"), (offset, index)] => parse_field(*offset, index)?,
+            _ => return Err(Error::parse(1, header.trim(), format!("expected 'Game <index>', got '{}'", header))),
+        };
         let mut game = CubeGame::new(game_index);
-        for set_string in parts[1].split(";") {
-            let cube_set = CubeSet::from_string(set_string)?;
-            game.cube_sets.push(cube_set);
+        for (offset, set_string) in split_fields(sets, ';') {
+            let cube_set = CubeSet::from_string(set_string)
+                .map_err(|e| e.shifted(header.len() + 1 + offset))?;
+            game.add_cube_set(cube_set);
         }
         Ok(game)
     }
 
-    fn is_valid(&self, max_red: usize, max_green: usize, max_blue: usize) -> bool {
-        for cube_set in &self.cube_sets {
+    pub fn add_cube_set(&mut self, cube_set: CubeSet) {
+        self.cube_sets.push(cube_set);
+    }
+
+    pub fn index(&self) -> usize {
+        self.game_index
+    }
+
+    pub fn cube_sets(&self) -> &[CubeSet] {
+        &self.cube_sets
+    }
+
+    pub fn is_valid(&self, max_red: usize, max_green: usize, max_blue: usize) -> bool {
+        for (set_index, cube_set) in self.cube_sets.iter().enumerate() {
             let mut reds = 0;
             let mut greens = 0;
             let mut blues = 0;
@@ -73,13 +124,15 @@ impl CubeGame {
                 }
             }
             if reds > max_red || greens > max_green || blues > max_blue {
+                crate::trace!(2, "game {}: set {} has {} red, {} green, {} blue (max {}, {}, {})",
+                    self.game_index, set_index + 1, reds, greens, blues, max_red, max_green, max_blue);
                 return false;
             }
         }
-        return true;
+        true
     }
 
-    fn cube_power(&self) -> usize {        
+    pub fn cube_power(&self) -> usize {        
         let mut min_reds = 0;
         let mut min_greens = 0;
         let mut min_blues = 0;
@@ -96,27 +149,105 @@ impl CubeGame {
     }
 }
 
-pub fn run_day_2<P>(path: P) -> Result<(), Box<dyn Error>> 
-where P: AsRef<Path> {
-    let file = File::open(&path)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    let mut games: Vec<CubeGame> = Vec::new();
-    while let Some(line) = lines.next() {
-        let line = line?;
-        games.push(CubeGame::from_string(&line)?);
-    }
-    let mut sum_valid_indexes = 0;
-    for game in &games {
-        if game.is_valid(12, 13, 14) {
-            sum_valid_indexes += game.game_index;
+pub struct CubeConundrum {
+    games: Vec<CubeGame>,
+}
+
+impl CubeConundrum {
+    pub fn games(&self) -> &[CubeGame] {
+        &self.games
+    }
+}
+
+impl Solution for CubeConundrum {
+    fn parse<R: BufRead>(reader: R) -> Result<CubeConundrum> {
+        let mut games: Vec<CubeGame> = Vec::new();
+        for (line_index, line) in input::lines(reader).enumerate() {
+            let line = line?;
+            games.push(CubeGame::from_string(&line).map_err(|e| e.at_line(line_index + 1, &line))?);
+        }
+        Ok(CubeConundrum { games })
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        let mut sum_valid_indexes = 0;
+        for game in &self.games {
+            let valid = game.is_valid(12, 13, 14);
+            crate::trace!(1, "game {}: {}", game.game_index, if valid { "possible" } else { "impossible" });
+            if valid {
+                sum_valid_indexes += game.game_index;
+            }
+        }
+        Ok(sum_valid_indexes as u64)
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        let mut sum_cube_power = 0;
+        for game in &self.games {
+            let cube_power = game.cube_power();
+            crate::trace!(1, "game {}: power {}", game.game_index, cube_power);
+            sum_cube_power += cube_power;
+        }
+        Ok(sum_cube_power as u64)
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("game <id>", "cube sets, possibility and power of a game"),
+            ("possible <r> <g> <b>", "games possible with at most r red, g green and b blue"),
+        ]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "game" => {
+                let id: usize = argument(args, 0, "id")?;
+                let game = self.games.iter()
+                    .find(|game| game.game_index == id)
+                    .ok_or_else(|| Error::invalid_query(format!("no game {}", id)))?;
+                let sets: Vec<String> = game.cube_sets.iter()
+                    .map(|cube_set| format!("{} red, {} green, {} blue",
+                        cube_set.count(Color::Red), cube_set.count(Color::Green), cube_set.count(Color::Blue)))
+                    .collect();
+                Ok(format!("game {}: {}; {}, power {}",
+                    id,
+                    sets.join("; "),
+                    if game.is_valid(12, 13, 14) { "possible" } else { "impossible" },
+                    game.cube_power()))
+            }
+            "possible" => {
+                let (red, green, blue) = (argument(args, 0, "r")?, argument(args, 1, "g")?, argument(args, 2, "b")?);
+                let ids: Vec<usize> = self.games.iter()
+                    .filter(|game| game.is_valid(red, green, blue))
+                    .map(|game| game.game_index)
+                    .collect();
+                Ok(format!("{:?}", ids))
+            }
+            _ => Err(repl::unknown_command(command)),
         }
     }
-    println!("Sum of valid indexes: {}", sum_valid_indexes);
-    let mut sum_cube_power = 0;
-    for game in &games {
-        sum_cube_power += game.cube_power();
+}
+
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    let mut output = String::new();
+    for game_index in 1..=options.size {
+        let sets: Vec<String> = (0..rng.range(1..options.width.max(1) + 1))
+            .map(|_| {
+                let mut colors = ["red", "green", "blue"];
+                rng.shuffle(&mut colors);
+                colors[..rng.range(1..4)]
+                    .iter()
+                    .map(|color| format!("{} {}", rng.range(1..21), color))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .collect();
+        output.push_str(&format!("Game {}: {}\n", game_index, sets.join("; ")));
     }
-    println!("Sum of cube power: {}", sum_cube_power);
-    Ok(())
+    output
+}
+
+pub fn run_day_2<P>(path: P) -> Result<Answer> 
+where P: AsRef<Path> {
+    Ok(CubeConundrum::parse_file(path.as_ref())?.solve())
 }
\ No newline at end of file
@@ -0,0 +1,94 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse { line: usize, column: usize, text: String, message: String, line_text: String },
+    MissingSection(String),
+    Unsolvable(String),
+    InvalidQuery(String),
+    InvalidVocabulary(String),
+    Skipped(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn parse<S: Into<String>>(column: usize, text: &str, message: S) -> Error {
+        Error::Parse { line: 0, column, text: text.to_string(), message: message.into(), line_text: String::new() }
+    }
+
+    pub fn unsolvable<S: Into<String>>(message: S) -> Error {
+        Error::Unsolvable(message.into())
+    }
+
+    pub fn invalid_query<S: Into<String>>(message: S) -> Error {
+        Error::InvalidQuery(message.into())
+    }
+
+    pub fn invalid_vocabulary<S: Into<String>>(message: S) -> Error {
+        Error::InvalidVocabulary(message.into())
+    }
+
+    pub fn skipped<S: Into<String>>(message: S) -> Error {
+        Error::Skipped(message.into())
+    }
+
+    pub fn at_line(self, line: usize, line_text: &str) -> Error {
+        match self {
+            Error::Parse { column, text, message, .. } => {
+                Error::Parse { line, column, text, message, line_text: line_text.to_string() }
+            }
+            error => error,
+        }
+    }
+
+    pub fn shifted_lines(self, offset: usize) -> Error {
+        match self {
+            Error::Parse { line, column, text, message, line_text } => {
+                Error::Parse { line: line + offset, column, text, message, line_text }
+            }
+            error => error,
+        }
+    }
+
+    pub fn shifted(self, offset: usize) -> Error {
+        match self {
+            Error::Parse { line, column, text, message, line_text } => {
+                Error::Parse { line, column: column + offset, text, message, line_text }
+            }
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Parse { line: 0, column, message, .. } => write!(f, "column {}: {}", column, message),
+            Error::Parse { line, column, message, .. } => write!(f, "line {}, column {}: {}", line, column, message),
+            Error::MissingSection(section) => write!(f, "missing section: {}", section),
+            Error::Unsolvable(message) => write!(f, "unsolvable input: {}", message),
+            Error::InvalidQuery(message) => write!(f, "invalid query: {}", message),
+            Error::InvalidVocabulary(message) => write!(f, "invalid vocabulary: {}", message),
+            Error::Skipped(message) => write!(f, "skipped: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
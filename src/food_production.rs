@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::io::BufRead;
 use std::path::Path;
 
+use crate::error::{Error, Result};
+use crate::generate::{GenOptions, Rng};
+use crate::input::{self, fields, parse_field};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+use crate::trace;
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Category {
     Seed,
@@ -33,17 +37,33 @@ impl MapEntry {
         }
     }
 
+    pub fn destination_range(&self) -> usize {
+        self.destination_range
+    }
+
+    pub fn source_range(&self) -> usize {
+        self.source_range
+    }
+
+    pub fn range_length(&self) -> usize {
+        self.range_length
+    }
+
+    // Ends are exclusive and saturate, so empty or oversized ranges from the
+    // input cannot underflow or overflow.
     pub fn filter_ranges(&self, ranges: &mut Vec<(usize, usize)>) -> Vec<(usize, usize)> {
         let mut transformed = Vec::new();
         let source_start = self.source_range;
-        let source_end = self.source_range + self.range_length - 1;
-        let range_diff = self.destination_range as i64 - self.source_range as i64;
+        let source_end = self.source_range.saturating_add(self.range_length);
 
         let mut untransformed = Vec::new();
         for element in ranges.drain(..) {
             let (start, length) = element;
-            let end = start + length - 1;
-            if start <= source_end && end >= source_start {
+            if length == 0 {
+                continue;
+            }
+            let end = start.saturating_add(length);
+            if start < source_end && end > source_start {
                 let new_start = if start >= source_start {
                     start
                 } else {
@@ -53,10 +73,11 @@ impl MapEntry {
                 let new_end = if end <= source_end {
                     end
                 } else {
-                    untransformed.push((source_end + 1, end - source_end));
+                    untransformed.push((source_end, end - source_end));
                     source_end
                 };
-                transformed.push(((new_start as i64 + range_diff) as usize, new_end - new_start + 1));
+                let destination = self.destination_range.saturating_add(new_start - source_start);
+                transformed.push((destination, new_end - new_start));
             }
             else {
                 untransformed.push(element);
@@ -80,14 +101,22 @@ impl Map {
         }
     }
 
-    fn add_entry(&mut self, entry: MapEntry) {
+    pub fn add_entry(&mut self, entry: MapEntry) {
         self.entries.push(entry);
     }
 
-    fn lookup(&self, value: usize) -> usize {
+    pub fn destination_category(&self) -> &Category {
+        &self.destination_category
+    }
+
+    pub fn entries(&self) -> &[MapEntry] {
+        &self.entries
+    }
+
+    pub fn lookup(&self, value: usize) -> usize {
         for entry in &self.entries {
-            if value >= entry.source_range && value < entry.source_range + entry.range_length {
-                return entry.destination_range + (value - entry.source_range);
+            if value >= entry.source_range && value < entry.source_range.saturating_add(entry.range_length) {
+                return entry.destination_range.saturating_add(value - entry.source_range);
             }
         }
         value
@@ -95,7 +124,7 @@ impl Map {
 
     pub fn lookup_ranges(&self, ranges: &[(usize, usize)]) -> Vec<(usize, usize)> {
         let mut transformed = Vec::new();
-        let mut untransformed: Vec::<(usize, usize)> = ranges.clone().to_vec();
+        let mut untransformed: Vec::<(usize, usize)> = ranges.to_vec();
         for entry in &self.entries {
             transformed.append(&mut entry.filter_ranges(&mut untransformed));
         }
@@ -104,6 +133,9 @@ impl Map {
     }
 }
 
+// The reference walk gives up on inputs with more seeds than this.
+const REFERENCE_SEED_LIMIT: usize = 100_000_000;
+
 pub struct FeedingAlmanac {
     production_maps: HashMap<Category, Map>
 }
@@ -121,23 +153,34 @@ impl FeedingAlmanac {
         ].iter().cloned().collect()
     }
 
-    fn parse_map_entry(line: &str) -> Result<MapEntry, ParseIntError> {
-        let map_values = line
-            .split_whitespace()
-            .map(|s| s.parse::<usize>())
-            .collect::<Result<Vec<usize>, ParseIntError>>()?;
+    fn section_header(category: &Category) -> String {
+        Self::create_transitions()
+            .into_iter()
+            .find(|(_, (source, _))| source == category)
+            .map_or(format!("{:?} map", category), |(header, _)| header.trim_end_matches(':').to_string())
+    }
+
+    fn parse_map_entry(line: &str) -> Result<MapEntry> {
+        let map_values = fields(line)
+            .into_iter()
+            .map(|(offset, s)| parse_field::<usize>(offset, s))
+            .collect::<Result<Vec<usize>>>()?;
+        if map_values.len() != 3 {
+            return Err(Error::parse(1, line.trim(), format!(
+                "expected '<destination> <source> <length>', got {} values", map_values.len())));
+        }
         let (destination_range, source_range, range_length) = 
             (map_values[0], map_values[1], map_values[2]);
         Ok(MapEntry::new(destination_range, source_range, range_length))
     }
 
-    fn from_lines(lines: Vec<String>) -> Result<FeedingAlmanac, Box<dyn Error>> {
+    pub fn from_lines(lines: Vec<String>) -> Result<FeedingAlmanac> {
         let mut current_section: Option<&(Category, Category)> = None;
         let mut production_maps: HashMap<Category, Map> = HashMap::new();
 
         let transitions = Self::create_transitions();
 
-        'lines: for line in lines {
+        'lines: for (line_index, line) in lines.into_iter().enumerate() {
             if line.is_empty() {
                 current_section = None;
                 continue 'lines;
@@ -148,14 +191,16 @@ impl FeedingAlmanac {
                 continue 'lines;
             }
             
-            let map_entry = Self::parse_map_entry(&line)?;
+            let map_entry = Self::parse_map_entry(&line).map_err(|e| e.at_line(line_index + 1, &line))?;
+            let (source, destination) = current_section
+                .ok_or_else(|| Error::parse(1, line.trim(), "map entry before any section header").at_line(line_index + 1, &line))?;
 
-            match production_maps.entry(current_section.ok_or("No current section")?.0.clone()) {
+            match production_maps.entry(source.clone()) {
                 Entry::Occupied(mut entry) => {
                     entry.get_mut().add_entry(map_entry);
                 }
                 Entry::Vacant(entry) => {
-                    let mut map = Map::new(current_section.ok_or("No current section")?.1.clone());
+                    let mut map = Map::new(destination.clone());
                     map.add_entry(map_entry);
                     entry.insert(map);
                 }
@@ -167,119 +212,256 @@ impl FeedingAlmanac {
         })
     }
 
-    fn get_location(&self, seed: usize) -> Result<usize, Box<dyn Error>> {
+    pub fn location_path(&self, seed: usize) -> Result<Vec<(Category, usize)>> {
         let mut current_map = self
             .get_map(Category::Seed)
-            .ok_or("No map for Seed")?;
+            .ok_or_else(|| Error::MissingSection(Self::section_header(&Category::Seed)))?;
         let mut value = current_map.lookup(seed);
+        let mut path = vec![(Category::Seed, seed), (current_map.destination_category.clone(), value)];
+        crate::trace!(2, "seed {}: {:?} {}", seed, current_map.destination_category, value);
 
         while current_map.destination_category != Category::Location {
             current_map = self
                 .get_map(current_map.destination_category.clone())
-                .ok_or(format!("No map for {:?}", current_map.destination_category))?;
+                .ok_or_else(|| Error::MissingSection(Self::section_header(&current_map.destination_category)))?;
 
             value = current_map.lookup(value);
+            path.push((current_map.destination_category.clone(), value));
+            crate::trace!(2, "seed {}: {:?} {}", seed, current_map.destination_category, value);
         }
-        Ok(value)
+        Ok(path)
     }
 
-    pub fn get_location_ranges(&self, seed_ranges: &[(usize, usize)]) -> Result<Vec<(usize, usize)>, Box<dyn Error>> {
+    pub fn get_location(&self, seed: usize) -> Result<usize> {
+        let path = self.location_path(seed)?;
+        Ok(path.last().map_or(seed, |&(_, location)| location))
+    }
+
+    pub fn get_location_ranges(&self, seed_ranges: &[(usize, usize)]) -> Result<Vec<(usize, usize)>> {
         let mut current_map = self
             .get_map(Category::Seed)
-            .ok_or("No map for Seed")?;
+            .ok_or_else(|| Error::MissingSection(Self::section_header(&Category::Seed)))?;
         let mut ranges = current_map.lookup_ranges(seed_ranges);
+        crate::trace!(1, "{:?}: {} range(s)", current_map.destination_category, ranges.len());
 
         while current_map.destination_category != Category::Location {
             current_map = self
                 .get_map(current_map.destination_category.clone())
-                .ok_or(format!("No map for {:?}", current_map.destination_category))?;
+                .ok_or_else(|| Error::MissingSection(Self::section_header(&current_map.destination_category)))?;
 
             ranges = current_map.lookup_ranges(&ranges);
+            crate::trace!(1, "{:?}: {} range(s)", current_map.destination_category, ranges.len());
         }
         Ok(ranges)
     }
 
-    fn check_seeds(&self, seeds: &[usize]) -> Result<usize, Box<dyn Error>> {
+    fn reference_location(&self, seed: usize) -> Result<usize> {
+        let mut category = Category::Seed;
+        let mut value = seed;
+        while category != Category::Location {
+            let map = self
+                .get_map(category.clone())
+                .ok_or_else(|| Error::MissingSection(Self::section_header(&category)))?;
+            value = map.lookup(value);
+            category = map.destination_category.clone();
+        }
+        Ok(value)
+    }
+
+    // Maps every seed on its own, so it only suits small inputs.
+    pub fn reference_lowest_location(&self, seed_ranges: &[(usize, usize)]) -> Result<usize> {
+        let seeds = seed_ranges.iter().fold(0usize, |seeds, &(_, length)| seeds.saturating_add(length));
+        if seeds > REFERENCE_SEED_LIMIT {
+            return Err(Error::skipped(format!("{} seeds are too many to map one by one", seeds)));
+        }
+        let mut lowest: Option<usize> = None;
+        for &(start, length) in seed_ranges {
+            for seed in start..start.saturating_add(length) {
+                let location = self.reference_location(seed)?;
+                lowest = Some(lowest.map_or(location, |lowest| lowest.min(location)));
+            }
+        }
+        lowest.ok_or_else(|| Error::unsolvable("no seed ranges to map"))
+    }
+
+    pub fn check_seeds(&self, seeds: &[usize]) -> Result<usize> {
         let mut location_min = usize::MAX;
         for seed in seeds {
-            match self.get_location(*seed) {
-                Ok(location) => {
-                    location_min = if location < location_min {
-                        location 
-                    } else { 
-                        location_min
-                    };
-                },
-                Err(e) => {
-                    println!("Failed to get location for seed {}: {}", seed, e);
-                }
-            }
+            let location = self.get_location(*seed)?;
+            crate::trace!(1, "seed {}: location {}", seed, location);
+            location_min = if location < location_min {
+                location 
+            } else { 
+                location_min
+            };
         }
         Ok(location_min)
     }
 
-    fn get_map(&self, category: Category) -> Option<&Map> {
+    pub fn get_map(&self, category: Category) -> Option<&Map> {
         self.production_maps.get(&category)
     }
 
 }
 
-pub fn run_day_5<P>(path: P) -> Result<(), Box<dyn Error>> 
-where P: AsRef<Path> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-
-    let seed_line = lines.next().ok_or("Expected a line")??;
-    let seeds = seed_line
-        .split(":")
-        .nth(1)
-        .ok_or("Expected a ':' in the line")?
-        .split_whitespace()
-        .map(|s| s.parse::<usize>())
-        .collect::<Result<Vec<usize>, ParseIntError>>()?;
-
-    println!("seeds: {:?}", seeds);
-    let almanac = FeedingAlmanac::from_lines(
-        lines.map(|l| l.unwrap()).collect::<Vec<String>>())?;
-
-    let location_min = almanac.check_seeds(&seeds)?;
-    println!("minimum location for individual seeds: {}", location_min);
-
-    let seed_ranges: Vec<(usize, usize)> = seeds
-        .chunks(2)
-        .filter_map(|chunk| {
-            if chunk.len() == 2 {
-                let start = chunk[0];
-                let count = chunk[1];
-                Some((start, count)) // Added closing parenthesis here
-            } else {
-                println!("Invalid chunk: {:?}", chunk);
-                None
-            }
-        })
-        .collect();
+pub struct FoodProduction {
+    seeds: Vec<usize>,
+    almanac: FeedingAlmanac,
+}
 
-    let location_ranges = almanac.get_location_ranges(&seed_ranges)?;
-    let first_items: Vec<usize> = location_ranges
-        .iter()
-        .map(|&(first, _)| first)
-        .collect();
+impl FoodProduction {
+    pub fn seeds(&self) -> &[usize] {
+        &self.seeds
+    }
+
+    pub fn almanac(&self) -> &FeedingAlmanac {
+        &self.almanac
+    }
+
+    pub fn seed_ranges(&self) -> Vec<(usize, usize)> {
+        self.seeds
+            .chunks(2)
+            .filter_map(|chunk| {
+                if chunk.len() == 2 {
+                    let start = chunk[0];
+                    let count = chunk[1];
+                    Some((start, count))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
 
-    let smallest_location = first_items.iter().min().unwrap();
-    
-    let seed_ranges_sum: usize = seed_ranges
-        .iter()
-        .map(|&(_, second)| second)
-        .sum();
+impl Solution for FoodProduction {
+    fn parse<R: BufRead>(reader: R) -> Result<FoodProduction> {
+        let mut lines = input::lines(reader);
+
+        let seed_line = lines.next().ok_or_else(|| Error::MissingSection("seeds".to_string()))??;
+        let (header, seeds_string) = seed_line
+            .split_once(':')
+            .ok_or_else(|| Error::parse(seed_line.len() + 1, "", "expected a ':' after 'seeds'").at_line(1, &seed_line))?;
+        let seeds = fields(seeds_string)
+            .into_iter()
+            .map(|(offset, s)| parse_field::<usize>(header.len() + 1 + offset, s))
+            .collect::<Result<Vec<usize>>>()
+            .map_err(|e| e.at_line(1, &seed_line))?;
+
+        let almanac = FeedingAlmanac::from_lines(
+            lines.collect::<std::io::Result<Vec<String>>>()?)
+            .map_err(|e| e.shifted_lines(1))?;
+
+        Ok(FoodProduction { seeds, almanac })
+    }
 
-    let location_ranges_sum: usize = location_ranges
-        .iter()
-        .map(|&(_, second)| second)
-        .sum();
-    println!("seed_ranges_sum: {}, location_ranges_sum: {}", seed_ranges_sum, location_ranges_sum);
+    fn part_one(&self) -> Result<u64> {
+        let location_min = self.almanac.check_seeds(&self.seeds)?;
+        Ok(location_min as u64)
+    }
 
-    println!("smallest location: {}", smallest_location);
+    fn part_two(&self) -> Result<u64> {
+        let location_ranges = self.almanac.get_location_ranges(&self.seed_ranges())?;
+        let smallest_location = location_ranges
+            .iter()
+            .map(|&(first, _)| first)
+            .min()
+            .ok_or_else(|| Error::unsolvable("no seed ranges to map"))?;
+        Ok(smallest_location as u64)
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        Some(self.almanac.reference_lowest_location(&self.seed_ranges()).map(|location| location as u64))
+    }
 
-    Ok(())
+    fn diagnostics(&self) -> Vec<(&'static str, String)> {
+        let mut diagnostics = vec![("seeds", format!("{:?}", self.seeds))];
+        let remainder = self.seeds.chunks_exact(2).remainder();
+        if !remainder.is_empty() {
+            diagnostics.push(("invalid_chunk", format!("{:?}", remainder)));
+        }
+
+        let seed_ranges = self.seed_ranges();
+        let seed_ranges_sum: usize = seed_ranges
+            .iter()
+            .map(|&(_, second)| second)
+            .sum();
+        diagnostics.push(("seed_ranges_sum", seed_ranges_sum.to_string()));
+
+        if let Ok(location_ranges) = trace::silenced(|| self.almanac.get_location_ranges(&seed_ranges)) {
+            let location_ranges_sum: usize = location_ranges
+                .iter()
+                .map(|&(_, second)| second)
+                .sum();
+            diagnostics.push(("location_ranges_sum", location_ranges_sum.to_string()));
+        }
+        diagnostics
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[("location <seed>", "every category a seed maps through on its way to a location")]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "location" => {
+                let path = self.almanac.location_path(argument(args, 0, "seed")?)?;
+                let steps: Vec<String> = path.iter()
+                    .map(|(category, value)| format!("{:?} {}", category, value))
+                    .collect();
+                Ok(steps.join(" -> "))
+            }
+            _ => Err(repl::unknown_command(command)),
+        }
+    }
+}
+
+fn generate_map(rng: &mut Rng, entries: usize, space: usize) -> Vec<(usize, usize, usize)> {
+    let mut cuts: Vec<usize> = (1..entries).map(|_| rng.range(1..space)).collect();
+    cuts.push(0);
+    cuts.push(space);
+    cuts.sort_unstable();
+    cuts.dedup();
+    let mut segments: Vec<(usize, usize)> = cuts.windows(2).map(|cut| (cut[0], cut[1] - cut[0])).collect();
+    rng.shuffle(&mut segments);
+    let mut destination = 0;
+    let mut map: Vec<(usize, usize, usize)> = Vec::new();
+    for &(source, length) in &segments {
+        map.push((destination, source, length));
+        destination += length;
+    }
+    rng.shuffle(&mut map);
+    map
+}
+
+// The value space grows with the input shape, so small inputs keep few
+// enough seeds for the reference solver to map each one.
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    let space = 1_000 * options.size.max(1) * options.width.max(1);
+    let seeds: Vec<String> = (0..options.size.max(1))
+        .flat_map(|_| [rng.range(0..space / 2), rng.range(1..space / 20)])
+        .map(|value| value.to_string())
+        .collect();
+    let mut output = format!("seeds: {}\n", seeds.join(" "));
+    let categories = [
+        Category::Seed,
+        Category::Soil,
+        Category::Fertilizer,
+        Category::Water,
+        Category::Light,
+        Category::Temperature,
+        Category::Humidity,
+    ];
+    for category in &categories {
+        output.push_str(&format!("\n{}:\n", FeedingAlmanac::section_header(category)));
+        for (destination, source, length) in generate_map(rng, options.width.max(1), space) {
+            output.push_str(&format!("{} {} {}\n", destination, source, length));
+        }
+    }
+    output
+}
+
+pub fn run_day_5<P>(path: P) -> Result<Answer> 
+where P: AsRef<Path> {
+    Ok(FoodProduction::parse_file(path.as_ref())?.solve())
 }
@@ -1,31 +1,48 @@
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
-struct Position {
-    x: i32,
-    y: i32,
+use crate::error::{Error, Result};
+use crate::input;
+use crate::generate::{GenOptions, Rng};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
 }
 
 impl Position {
-    fn new(x: i32, y: i32) -> Position {
+    pub fn new(x: i32, y: i32) -> Position {
         Position { x, y }
     }
 }
 
-struct PartNumber {
+pub struct PartNumber {
     id: i32,
     position: Position,
     num_digits: i32,
 }
 
 impl PartNumber {
-    fn new(id: i32, position: Position, num_digits: i32) -> PartNumber {
+    pub fn new(id: i32, position: Position, num_digits: i32) -> PartNumber {
         PartNumber { id, position, num_digits }
     }
 
-    fn is_near(&self, symbol: &Symbol) -> bool {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn num_digits(&self) -> i32 {
+        self.num_digits
+    }
+
+    pub fn is_near(&self, symbol: &Symbol) -> bool {
         let x_near = symbol.position.x >= self.position.x - 1 && 
             symbol.position.x <= self.position.x + self.num_digits;
         let y_near = (self.position.y - symbol.position.y).abs() <= 1;
@@ -33,15 +50,23 @@ impl PartNumber {
     }
 }
 
-struct Symbol {
-    _value: char,
+pub struct Symbol {
+    value: char,
     position: Position,
 }
 
 impl Symbol {
-    fn new(_value: char, position: Position) -> Symbol {
-        Symbol { _value, position }
-    }    
+    pub fn new(value: char, position: Position) -> Symbol {
+        Symbol { value, position }
+    }
+
+    pub fn value(&self) -> char {
+        self.value
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
 }
 
 fn parse_number(number_pending: &mut Option<(i32, usize)>, char_index: usize, line_index: usize, part_numbers: &mut Vec<PartNumber>) {
@@ -61,54 +86,161 @@ fn parse_symbol(c: char, char_index: usize, line_index: usize, symbols: &mut Vec
     }
 }
 
-fn parse_digit(digit: Option<u32>, number_pending: &mut Option<(i32, usize)>) {
+fn parse_digit(digit: Option<u32>, char_index: usize, line: &str, number_pending: &mut Option<(i32, usize)>) -> Result<()> {
     if let Some(digit) = digit {
         let (number, num_digits) = number_pending.get_or_insert((0, 0));
-        *number = *number * 10 + digit as i32;
+        *number = number.checked_mul(10)
+            .and_then(|number| number.checked_add(digit as i32))
+            .ok_or_else(|| {
+                let start = char_index - *num_digits;
+                let end = line[start..].find(|c: char| !c.is_ascii_digit()).map_or(line.len(), |end| start + end);
+                Error::parse(start + 1, &line[start..end], format!("part number '{}' is too large", &line[start..end]))
+            })?;
         *num_digits += 1;
     }
+    Ok(())
 }
 
-pub fn run_day_3<P>(path: P) -> Result<(), Box<dyn Error>> 
-where P: AsRef<Path> {
-    let file = File::open(&path)?;
-    let reader = BufReader::new(file);
-    let lines = reader.lines();
-    let mut part_numbers: Vec<PartNumber> = Vec::new();
-    let mut symbols: Vec<Symbol> = Vec::new();
-    for (line_index, line) in lines.enumerate() {
-        let line = line?;
-        let mut number_pending: Option<(i32, usize)> = None;
-        for (char_index, c) in line.char_indices() {
-            let digit = c.to_digit(10);
-            parse_digit(digit, &mut number_pending);
-            if digit.is_none() {
-                parse_number(&mut number_pending, char_index, line_index, &mut part_numbers);
-                parse_symbol(c, char_index, line_index, &mut symbols);
+pub struct GearRatios {
+    part_numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+}
+
+impl GearRatios {
+    pub fn part_numbers(&self) -> &[PartNumber] {
+        &self.part_numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn parts_near(&self, symbol: &Symbol) -> Vec<&PartNumber> {
+        self.part_numbers.iter()
+            .filter(|part_number| part_number.is_near(symbol))
+            .collect()
+    }
+}
+
+impl Solution for GearRatios {
+    fn parse<R: BufRead>(reader: R) -> Result<GearRatios> {
+        let lines = input::lines(reader);
+        let mut part_numbers: Vec<PartNumber> = Vec::new();
+        let mut symbols: Vec<Symbol> = Vec::new();
+        for (line_index, line) in lines.enumerate() {
+            let line = line?;
+            let mut number_pending: Option<(i32, usize)> = None;
+            for (char_index, c) in line.char_indices() {
+                let digit = c.to_digit(10);
+                parse_digit(digit, char_index, &line, &mut number_pending).map_err(|e| e.at_line(line_index + 1, &line))?;
+                if digit.is_none() {
+                    parse_number(&mut number_pending, char_index, line_index, &mut part_numbers);
+                    parse_symbol(c, char_index, line_index, &mut symbols);
+                }
             }
+            parse_number(&mut number_pending, line.len(), line_index, &mut part_numbers);
         }
-        parse_number(&mut number_pending, line.len(), line_index, &mut part_numbers);
-    }
-    let valid_part_number_sum: i32 = part_numbers.iter()
-        .filter(|part_number| symbols.iter().any(|symbol| part_number.is_near(symbol)))
-        .map(|part_number| part_number.id)
-        .sum();
-
-    println!("Sum of valid part numbers: {}", valid_part_number_sum);
-
-    let gear_ratio_sum: i32 = symbols.iter()
-        .filter_map(|symbol| {
-            let near_parts: Vec<_> = part_numbers.iter()
-                .filter(|part_number| part_number.is_near(symbol))
-                .collect();
-            if near_parts.len() == 2 {
-                Some(near_parts.iter().map(|part_number| part_number.id).product::<i32>())
-            } else {
-                None
+        Ok(GearRatios { part_numbers, symbols })
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        let valid_part_number_sum: u64 = self.part_numbers.iter()
+            .filter(|part_number| {
+                let symbol = self.symbols.iter().find(|symbol| part_number.is_near(symbol));
+                match symbol {
+                    Some(symbol) => crate::trace!(1, "part {} at ({}, {}): next to '{}' at ({}, {})",
+                        part_number.id, part_number.position.x, part_number.position.y,
+                        symbol.value, symbol.position.x, symbol.position.y),
+                    None => crate::trace!(1, "part {} at ({}, {}): no adjacent symbol",
+                        part_number.id, part_number.position.x, part_number.position.y),
+                }
+                symbol.is_some()
+            })
+            .map(|part_number| part_number.id as u64)
+            .sum();
+        Ok(valid_part_number_sum)
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        let gear_ratio_sum = self.symbols.iter()
+            .filter_map(|symbol| {
+                let near_parts = self.parts_near(symbol);
+                crate::trace!(2, "'{}' at ({}, {}): adjacent parts {:?}", symbol.value, symbol.position.x, symbol.position.y,
+                    near_parts.iter().map(|part_number| part_number.id).collect::<Vec<_>>());
+                if near_parts.len() == 2 {
+                    Some(near_parts.iter().map(|part_number| part_number.id as u64).product::<u64>())
+                } else {
+                    None
+                }
+            })
+            .try_fold(0u64, |sum, ratio| sum.checked_add(ratio))
+            .ok_or_else(|| Error::unsolvable("gear ratio sum overflows u64"))?;
+        Ok(gear_ratio_sum)
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("symbol <x> <y>", "part numbers adjacent to the symbol at column x, row y"),
+            ("part <x> <y>", "symbols adjacent to the part number covering column x, row y"),
+        ]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        let position = || -> Result<Position> { Ok(Position::new(argument(args, 0, "x")?, argument(args, 1, "y")?)) };
+        match command {
+            "symbol" => {
+                let position = position()?;
+                let symbol = self.symbols.iter()
+                    .find(|symbol| symbol.position == position)
+                    .ok_or_else(|| Error::invalid_query(format!("no symbol at ({}, {})", position.x, position.y)))?;
+                let ids: Vec<i32> = self.parts_near(symbol).iter().map(|part_number| part_number.id).collect();
+                Ok(format!("'{}': adjacent parts {:?}", symbol.value, ids))
+            }
+            "part" => {
+                let position = position()?;
+                let part_number = self.part_numbers.iter()
+                    .find(|part_number| part_number.position.y == position.y
+                        && position.x >= part_number.position.x
+                        && position.x < part_number.position.x + part_number.num_digits)
+                    .ok_or_else(|| Error::invalid_query(format!("no part number at ({}, {})", position.x, position.y)))?;
+                let symbols: Vec<String> = self.symbols.iter()
+                    .filter(|symbol| part_number.is_near(symbol))
+                    .map(|symbol| format!("'{}' at ({}, {})", symbol.value, symbol.position.x, symbol.position.y))
+                    .collect();
+                Ok(format!("{}: adjacent symbols [{}]", part_number.id, symbols.join(", ")))
             }
-        })
-        .sum();
+            _ => Err(repl::unknown_command(command)),
+        }
+    }
+}
 
-    println!("Sum of gear ratios: {}", gear_ratio_sum);
-    Ok(())
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '@', '/', '=', '%', '&', '-'];
+    let width = options.width.max(3);
+    let mut output = String::new();
+    for _ in 0..options.size {
+        let mut row = String::new();
+        while row.len() < width {
+            let room = width - row.len();
+            match rng.range(0..10) {
+                0..=2 => {
+                    let digits = rng.range(1..4).min(room);
+                    row.push_str(&rng.range(10usize.pow(digits as u32 - 1)..10usize.pow(digits as u32)).to_string());
+                    if row.len() < width {
+                        row.push(if rng.chance(1, 3) { *rng.choose(&SYMBOLS) } else { '.' });
+                    }
+                }
+                3 => row.push(*rng.choose(&SYMBOLS)),
+                _ => row.push('.'),
+            }
+        }
+        output.push_str(&row);
+        output.push('\n');
+    }
+    output
+}
+
+pub fn run_day_3<P>(path: P) -> Result<Answer> 
+where P: AsRef<Path> {
+    Ok(GearRatios::parse_file(path.as_ref())?.solve())
 }
\ No newline at end of file
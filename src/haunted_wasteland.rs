@@ -1,11 +1,16 @@
 use std::collections::{HashMap, HashSet};
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
-#[derive(Debug)]
-enum Instruction {
+use crate::error::{Error, Result};
+use crate::input;
+use crate::generate::{GenOptions, Rng};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+use crate::trace;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
     Left,
     Right
 }
@@ -18,83 +23,204 @@ fn gcd(a: usize, b: usize) -> usize {
     }
 }
 
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
+fn lcm(a: usize, b: usize) -> Option<usize> {
+    (a / gcd(a, b)).checked_mul(b)
 }
 
-pub fn run_day_8<P>(path: P) -> Result<(), Box<dyn Error>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-
-    let instructions = lines
-        .next().ok_or("Expected line")??
-        .chars()
-        .map(|c| match c {
-            'L' => Instruction::Left,
-            'R' => Instruction::Right,
-            _ => panic!("Invalid instruction"),
+fn parse_instructions(line: &str) -> Result<Vec<Instruction>> {
+    if line.is_empty() {
+        return Err(Error::MissingSection("instructions".to_string()));
+    }
+    line.char_indices()
+        .map(|(index, c)| match c {
+            'L' => Ok(Instruction::Left),
+            'R' => Ok(Instruction::Right),
+            _ => Err(Error::parse(index + 1, &line[index..index + c.len_utf8()], format!("Invalid instruction '{}'", c))),
         })
-        .collect::<Vec<Instruction>>();
-
-    let nodes: HashMap<String, (String, String)> = lines
-        .filter_map(|line| line.ok())
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| {
-            let key = line[0..3].to_string();
-            let left = line[7..10].to_string();
-            let right = line[12..15].to_string();
-            (key, (left, right))
-    }).collect();
-
-    let start = nodes.get("AAA");
-    let end = nodes.get("ZZZ");
-    
-    if let (Some(start), Some(end)) = (start, end) {
+        .collect()
+}
+
+// Every node line has exactly this shape, with '_' standing for a letter
+// or digit of a node name.
+const NODE_SHAPE: &str = "___ = (___, ___)";
+
+fn parse_node(line: &str) -> Result<(String, (String, String))> {
+    let expected = || format!("expected 'AAA = (BBB, CCC)', got '{}'", line);
+    let mut shape = NODE_SHAPE.chars();
+    for (index, c) in line.char_indices() {
+        let matches = match shape.next() {
+            Some('_') => c.is_ascii_alphanumeric(),
+            Some(literal) => c == literal,
+            None => false,
+        };
+        if !matches {
+            let text = if index < NODE_SHAPE.len() { &line[index..index + c.len_utf8()] } else { &line[index..] };
+            return Err(Error::parse(index + 1, text, expected()));
+        }
+    }
+    if line.len() < NODE_SHAPE.len() {
+        return Err(Error::parse(line.len() + 1, "", expected()));
+    }
+    Ok((line[0..3].to_string(), (line[7..10].to_string(), line[12..15].to_string())))
+}
+
+// Reference walks give up after this many steps instead of looping forever.
+const REFERENCE_STEP_LIMIT: usize = 100_000_000;
+
+// Skips a reference walk up front when the optimized answer already lies
+// beyond the step limit, instead of walking until the limit first.
+fn check_step_limit(optimized: Result<u64>) -> Result<()> {
+    match optimized {
+        Ok(steps) if steps > REFERENCE_STEP_LIMIT as u64 => Err(Error::skipped(
+            format!("{} steps are beyond the reference limit of {}", steps, REFERENCE_STEP_LIMIT))),
+        _ => Ok(()),
+    }
+}
+
+pub struct HauntedWasteland {
+    instructions: Vec<Instruction>,
+    nodes: HashMap<String, (String, String)>,
+}
+
+impl HauntedWasteland {
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    pub fn nodes(&self) -> &HashMap<String, (String, String)> {
+        &self.nodes
+    }
+
+    pub fn next(&self, node: &str, instruction: Instruction) -> Option<&str> {
+        let (left, right) = self.nodes.get(node)?;
+        match instruction {
+            Instruction::Left => Some(left),
+            Instruction::Right => Some(right),
+        }
+    }
+
+    pub fn walk<'a>(&'a self, start: &'a str, steps: usize) -> Result<&'a str> {
+        let mut current = start;
+        for step in 0..steps {
+            let instruction = self.instructions[step % self.instructions.len()];
+            current = self.next(current, instruction)
+                .ok_or_else(|| Error::unsolvable(format!("node '{}' is not defined", current)))?;
+        }
+        Ok(current)
+    }
+
+    pub fn steps_to_end(&self, start: &str) -> Result<usize> {
+        let limit = self.instructions.len() * self.nodes.len().max(1);
         let mut current = start;
+        for step in 0..=limit {
+            if step > 0 && current.ends_with('Z') {
+                return Ok(step);
+            }
+            let instruction = self.instructions[step % self.instructions.len()];
+            current = self.next(current, instruction)
+                .ok_or_else(|| Error::unsolvable(format!("node '{}' is not defined", current)))?;
+        }
+        Err(Error::unsolvable(format!("'{}' never reaches a node ending in 'Z'", start)))
+    }
+
+    // Moves every ghost one step at a time until all of them stand on a node
+    // ending in 'Z' together.
+    fn reference_steps<F>(&self, starts: Vec<&str>, at_end: F) -> Result<u64>
+    where F: Fn(&str) -> bool {
+        if starts.is_empty() {
+            return Err(Error::unsolvable("no start nodes"));
+        }
+        let mut current = starts;
+        for step in 0..REFERENCE_STEP_LIMIT {
+            if step > 0 && current.iter().all(|node| at_end(node)) {
+                return Ok(step as u64);
+            }
+            let instruction = self.instructions[step % self.instructions.len()];
+            for node in current.iter_mut() {
+                *node = self.next(node, instruction)
+                    .ok_or_else(|| Error::unsolvable(format!("node '{}' is not defined", node)))?;
+            }
+        }
+        Err(Error::skipped(format!("gave up after {} steps", REFERENCE_STEP_LIMIT)))
+    }
+}
+
+impl Solution for HauntedWasteland {
+    fn parse<R: BufRead>(reader: R) -> Result<HauntedWasteland> {
+        let mut lines = input::lines(reader);
+
+        let instruction_line = lines.next().ok_or_else(|| Error::MissingSection("instructions".to_string()))??;
+        let instructions = parse_instructions(&instruction_line).map_err(|e| e.at_line(1, &instruction_line))?;
+
+        let mut nodes: HashMap<String, (String, String)> = HashMap::new();
+        for (line_index, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, next) = parse_node(&line).map_err(|e| e.at_line(line_index + 2, &line))?;
+            nodes.insert(key, next);
+        }
+
+        Ok(HauntedWasteland { instructions, nodes })
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        if !self.nodes.contains_key("AAA") || !self.nodes.contains_key("ZZZ") {
+            return Err(Error::unsolvable("'AAA' or 'ZZZ' not found in nodes"));
+        }
+
+        let limit = self.instructions.len() * self.nodes.len();
+        let mut current = "AAA";
         let mut count = 0;
-    
-        while current != end {
-            let (left, right) = current;
-            let next = match instructions[count % instructions.len()] {
-                Instruction::Left => left,
-                Instruction::Right => right
-            };
-            current = nodes.get(next).ok_or("Invalid node")?;
+        while current != "ZZZ" {
+            if count == limit {
+                return Err(Error::unsolvable("'AAA' never reaches 'ZZZ'"));
+            }
+            let instruction = self.instructions[count % self.instructions.len()];
+            current = self.next(current, instruction)
+                .ok_or_else(|| Error::unsolvable(format!("node '{}' is not defined", current)))?;
+            crate::trace!(2, "step {}: {:?} to {}", count + 1, instruction, current);
             count += 1;
         }
-    
-        println!("Count for AAA to ZZZ: {}", count);
-    } else {
-        println!("'AAA' or 'ZZZ' not found in nodes");
+
+        crate::trace!(1, "AAA reaches ZZZ after {} steps", count);
+        Ok(count as u64)
     }
 
-    let start_nodes: HashSet<&str> = nodes
-        .keys()
-        .filter(|key| key.ends_with("A"))
-        .map(String::as_str)
-        .collect();
+    fn part_two(&self) -> Result<u64> {
+        let nodes = &self.nodes;
+        let instructions = &self.instructions;
+        let start_nodes: HashSet<&str> = nodes
+            .keys()
+            .filter(|key| key.ends_with("A"))
+            .map(String::as_str)
+            .collect();
 
-    let end_nodes : HashSet<&str> = nodes
-        .keys()
-        .filter(|key| key.ends_with("Z"))
-        .map(String::as_str)
-        .collect();
+        let end_nodes : HashSet<&str> = nodes
+            .keys()
+            .filter(|key| key.ends_with("Z"))
+            .map(String::as_str)
+            .collect();
 
-    println!("Start nodes: {:?}", start_nodes);
-    println!("End nodes: {:?}", end_nodes);
-
-    let mut current_nodes: Vec<&str> = start_nodes.iter().cloned().collect();
-    let mut counts: Vec<usize> = vec![0; start_nodes.len()];
-    let mut end_counts: Vec<Option<usize>> = vec![None; start_nodes.len()];
-    
-    while end_counts.iter().any(|&count| count.is_none()) {
-        let mut next_nodes: Vec<&str> = Vec::with_capacity(current_nodes.len());
-        for ((i, node), count) in current_nodes.iter().enumerate().zip(&mut counts) {
-            if let Some((left, right)) = nodes.get(*node) {
+        let mut current_nodes: Vec<&str> = start_nodes.iter().cloned().collect();
+        let ghost_starts = current_nodes.clone();
+        let mut counts: Vec<usize> = vec![0; start_nodes.len()];
+        let mut end_counts: Vec<Option<usize>> = vec![None; start_nodes.len()];
+
+        // A ghost repeats its node and instruction position within this many
+        // steps, so one that has not reached an end node by then never will.
+        let limit = instructions.len() * nodes.len();
+        let mut steps = 0;
+        while end_counts.iter().any(|&count| count.is_none()) {
+            if steps > limit {
+                let stuck = end_counts.iter().position(|count| count.is_none()).unwrap_or(0);
+                return Err(Error::unsolvable(format!("'{}' never reaches a node ending in 'Z'", ghost_starts[stuck])));
+            }
+            let mut next_nodes: Vec<&str> = Vec::with_capacity(current_nodes.len());
+            for ((i, node), count) in current_nodes.iter().enumerate().zip(&mut counts) {
+                let (left, right) = nodes.get(*node)
+                    .ok_or_else(|| Error::unsolvable(format!("node '{}' is not defined", node)))?;
                 let next = match instructions[*count % instructions.len()] {
                     Instruction::Left => left,
                     Instruction::Right => right
@@ -102,20 +228,158 @@ where
                 next_nodes.push(next);
                 *count += 1;
                 if end_nodes.contains(next.as_str()) && end_counts[i].is_none() {
+                    crate::trace!(1, "{} first reaches {} after {} steps", ghost_starts[i], next, count);
                     end_counts[i] = Some(*count);
                 }
             }
+            current_nodes = next_nodes;
+            steps += 1;
+        }
+
+        let mut counts = end_counts.iter().filter_map(|&count| count);
+        let first = counts.next().ok_or_else(|| Error::unsolvable("no start nodes ending in 'A'"))?;
+        let lcm = counts
+            .try_fold(first, lcm)
+            .ok_or_else(|| Error::unsolvable("least common multiple of the steps overflows"))?;
+
+        Ok(lcm as u64)
+    }
+
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        if !self.nodes.contains_key("AAA") || !self.nodes.contains_key("ZZZ") {
+            return Some(Err(Error::unsolvable("'AAA' or 'ZZZ' not found in nodes")));
+        }
+        Some(check_step_limit(trace::silenced(|| self.part_one()))
+            .and_then(|_| self.reference_steps(vec!["AAA"], |node| node == "ZZZ")))
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        let starts: Vec<&str> = self.nodes
+            .keys()
+            .filter(|key| key.ends_with('A'))
+            .map(String::as_str)
+            .collect();
+        Some(check_step_limit(trace::silenced(|| self.part_two()))
+            .and_then(|_| self.reference_steps(starts, |node| node.ends_with('Z'))))
+    }
+
+    fn diagnostics(&self) -> Vec<(&'static str, String)> {
+        let mut start_nodes: Vec<&String> = self.nodes
+            .keys()
+            .filter(|key| key.ends_with("A"))
+            .collect();
+        start_nodes.sort_unstable();
+
+        let mut end_nodes: Vec<&String> = self.nodes
+            .keys()
+            .filter(|key| key.ends_with("Z"))
+            .collect();
+        end_nodes.sort_unstable();
+
+        vec![
+            ("start_nodes", format!("{:?}", start_nodes)),
+            ("end_nodes", format!("{:?}", end_nodes)),
+        ]
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("node <name>", "left and right neighbours of a node"),
+            ("walk <name> <steps>", "node reached after following the instructions for steps steps"),
+            ("steps <name>", "steps until a node ending in 'Z' is reached"),
+        ]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "node" => {
+                let name: String = argument(args, 0, "name")?;
+                let (left, right) = self.nodes.get(&name)
+                    .ok_or_else(|| Error::invalid_query(format!("no node '{}'", name)))?;
+                Ok(format!("{} = ({}, {})", name, left, right))
+            }
+            "walk" => {
+                let (name, steps): (String, usize) = (argument(args, 0, "name")?, argument(args, 1, "steps")?);
+                Ok(self.walk(&name, steps)?.to_string())
+            }
+            "steps" => {
+                let name: String = argument(args, 0, "name")?;
+                Ok(self.steps_to_end(&name)?.to_string())
+            }
+            _ => Err(repl::unknown_command(command)),
+        }
+    }
+}
+
+// Each ghost's cycle length is the instruction count times a distinct prime,
+// so more ghosts would overflow the least common multiple.
+const GHOST_PRIMES: [usize; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+
+fn generate_name(rng: &mut Rng, used: &mut HashSet<String>, last: Option<char>) -> String {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    loop {
+        let mut name: String = (0..3).map(|_| *rng.choose(CHARS) as char).collect();
+        if let Some(last) = last {
+            name.replace_range(2..3, &last.to_string());
+        } else if name.ends_with(['A', 'Z']) {
+            continue;
+        }
+        if used.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    let instructions: Vec<Instruction> = (0..options.width.clamp(1, 200))
+        .map(|_| if rng.chance(1, 2) { Instruction::Left } else { Instruction::Right })
+        .collect();
+    let mut used: HashSet<String> = ["AAA", "ZZZ"].iter().map(|name| name.to_string()).collect();
+    let trap = generate_name(rng, &mut used, None);
+    let mut nodes = vec![(trap.clone(), trap.clone(), trap.clone())];
+    let mut primes = GHOST_PRIMES;
+    rng.shuffle(&mut primes);
+
+    for (ghost, prime) in primes.iter().take(options.size.clamp(1, GHOST_PRIMES.len())).enumerate() {
+        let length = instructions.len() * prime;
+        let start = if ghost == 0 { "AAA".to_string() } else { generate_name(rng, &mut used, Some('A')) };
+        let end = if ghost == 0 { "ZZZ".to_string() } else { generate_name(rng, &mut used, Some('Z')) };
+        let mut path = vec![start];
+        path.extend((1..length).map(|_| generate_name(rng, &mut used, None)));
+        path.push(end.clone());
+
+        for (step, pair) in path.windows(2).enumerate() {
+            let (next, other) = (pair[1].clone(), trap.clone());
+            nodes.push(match instructions[step % instructions.len()] {
+                Instruction::Left => (pair[0].clone(), next, other),
+                Instruction::Right => (pair[0].clone(), other, next),
+            });
         }
-        current_nodes = next_nodes;
+        // The end node continues the cycle exactly like the start node.
+        nodes.push(match instructions[0] {
+            Instruction::Left => (end, path[1].clone(), trap.clone()),
+            Instruction::Right => (end, trap.clone(), path[1].clone()),
+        });
     }
-    
-    let lcm = end_counts
+    rng.shuffle(&mut nodes);
+
+    let mut output: String = instructions
         .iter()
-        .filter_map(|&count| count)
-        .reduce(lcm)
-        .unwrap();
-    
-    println!("Count for all XXA to XXZ: {}", lcm);
+        .map(|instruction| match instruction {
+            Instruction::Left => 'L',
+            Instruction::Right => 'R',
+        })
+        .collect();
+    output.push_str("\n\n");
+    for (node, left, right) in nodes {
+        output.push_str(&format!("{} = ({}, {})\n", node, left, right));
+    }
+    output
+}
 
-    Ok(())
+pub fn run_day_8<P>(path: P) -> Result<Answer>
+where
+    P: AsRef<Path>,
+{
+    Ok(HauntedWasteland::parse_file(path.as_ref())?.solve())
 }
\ No newline at end of file
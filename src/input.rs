@@ -0,0 +1,109 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl Source {
+    pub fn from_arg(arg: &Path) -> Source {
+        if arg == Path::new("-") {
+            Source::Stdin
+        } else {
+            Source::File(arg.to_path_buf())
+        }
+    }
+
+    pub fn open(&self) -> io::Result<Box<dyn BufRead + '_>> {
+        match self {
+            Source::File(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+            Source::Stdin => Ok(Box::new(io::stdin().lock())),
+            Source::Text(text) => Ok(Box::new(text.as_bytes())),
+        }
+    }
+
+    pub fn read_to_string(&self) -> io::Result<String> {
+        let mut text = String::new();
+        self.open()?.read_to_string(&mut text)?;
+        Ok(text)
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::Text(_) => write!(f, "<text>"),
+        }
+    }
+}
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+pub fn normalize_line(line: &str) -> &str {
+    line.strip_prefix(BYTE_ORDER_MARK).unwrap_or(line).trim_end()
+}
+
+pub struct Lines<R> {
+    lines: io::Lines<R>,
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        self.lines.next().map(|line| line.map(|line| normalize_line(&line).to_string()))
+    }
+}
+
+pub fn lines<R: BufRead>(reader: R) -> Lines<R> {
+    Lines { lines: reader.lines() }
+}
+
+pub fn parse_field<T>(offset: usize, field: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    field.parse::<T>()
+        .map_err(|e| Error::parse(offset + 1, field, format!("invalid number '{}': {}", field, e)))
+}
+
+pub fn split_fields(text: &str, separator: char) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    text.split(separator)
+        .map(|field| {
+            let start = offset;
+            offset += field.len() + separator.len_utf8();
+            (start, field)
+        })
+        .collect()
+}
+
+pub fn fields(text: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(field_start)) => {
+                fields.push((field_start, &text[field_start..index]));
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(field_start) = start {
+        fields.push((field_start, &text[field_start..]));
+    }
+    fields
+}
@@ -4,4 +4,21 @@ pub mod gear_ratios;
 pub mod scratchcards;
 pub mod food_production;
 pub mod boat_race;
-pub mod camel_cards;
\ No newline at end of file
+pub mod camel_cards;
+pub mod haunted_wasteland;
+pub mod solution;
+pub mod registry;
+pub mod runner;
+pub mod cli;
+pub mod output;
+pub mod input;
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod watch;
+pub mod trace;
+pub mod repl;
+pub mod scaffold;
+pub mod generate;
+pub mod crosscheck;
+pub mod matcher;
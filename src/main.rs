@@ -1,28 +1,308 @@
 // Purpose: Main entry point for the Advent of Code 2023 Rust solutions.
 use std::error::Error;
-pub mod trebuchet;
-pub mod cube_conundrum;
-pub mod gear_ratios;
-pub mod scratchcards;
-pub mod food_production;
-pub mod boat_race;
-pub mod camel_cards;
-pub mod haunted_wasteland;
-
-pub(crate) fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = std::env::args().collect();
-    let day = args[1].parse().unwrap();
-    let path = &args[2];
-    match day {
-        1 => return trebuchet::run_day_1(path),
-        2 => return cube_conundrum::run_day_2(path),
-        3 => return gear_ratios::run_day_3(path),
-        4 => return scratchcards::run_day_4(path),
-        5 => return food_production::run_day_5(path),
-        6 => return boat_race::run_day_6(path),
-        7 => return camel_cards::run_day_7(path),
-        8 => return haunted_wasteland::run_day_8(path), 
-        _ => return Err(From::from(format!("Day {} not implemented", day))),
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use advent_of_code_2023::answers::{self, Answers, CheckStatus, ANSWERS_FILE};
+use advent_of_code_2023::bench::{self, Baseline, BenchOptions};
+use advent_of_code_2023::cli::{self, Command, USAGE};
+use advent_of_code_2023::crosscheck::{self, CrosscheckOptions};
+use advent_of_code_2023::generate::{GenOptions, Rng};
+use advent_of_code_2023::input::Source;
+use advent_of_code_2023::output::{self, Format};
+use advent_of_code_2023::registry::{self, Day, DAYS};
+use advent_of_code_2023::repl;
+use advent_of_code_2023::scaffold;
+use advent_of_code_2023::runner::{self, DayReport};
+use advent_of_code_2023::solution::Solution;
+use advent_of_code_2023::trace;
+use advent_of_code_2023::trebuchet::{MissingDigitPolicy, Trebuchet, Vocabulary};
+use advent_of_code_2023::watch::Watcher;
+
+fn check_failures(reports: &[DayReport]) -> Result<(), Box<dyn Error>> {
+    let failures: usize = reports.iter().map(DayReport::failures).sum();
+    if failures > 0 {
+        return Err(From::from(format!("{} part(s) failed", failures)));
+    }
+    Ok(())
+}
+
+fn print_parse_errors(reports: &[DayReport]) {
+    for report in reports {
+        let snippet = report.parse_error.as_ref()
+            .and_then(|e| output::render_snippet(report.input.as_deref().unwrap_or("<input>"), e));
+        if let Some(snippet) = snippet {
+            eprint!("{}", snippet);
+        }
+    }
+}
+
+fn find_day(day_number: u32) -> Result<&'static Day, Box<dyn Error>> {
+    let day = registry::find_day(day_number)
+        .ok_or(format!("Day {} not implemented, see `list`", day_number))?;
+    Ok(day)
+}
+
+fn resolve_source(day: &Day, input: Option<&Path>) -> Result<Source, Box<dyn Error>> {
+    match input {
+        Some(path) => Ok(Source::from_arg(path)),
+        None => Ok(Source::File(day.input_path(Path::new("."))
+            .ok_or(format!("No input for day {} in the current directory, pass one with --input", day.number))?)),
+    }
+}
+
+fn run_day(day_number: u32, input: Option<&Path>, part: Option<u8>, time: bool, format: Format) -> Result<(), Box<dyn Error>> {
+    let day = find_day(day_number)?;
+    let source = resolve_source(day, input)?;
+    let reports = [runner::run_day(day, &source, part)];
+    match format {
+        Format::Text => print!("{}", output::render_text_report(&reports[0], time)),
+        _ => print!("{}", output::render(&reports, format, time)),
+    }
+    print_parse_errors(&reports);
+    check_failures(&reports)
+}
+
+fn run_all(dir: &Path, time: bool, format: Format, jobs: usize) -> Result<(), Box<dyn Error>> {
+    let reports = runner::run_all(dir, jobs);
+    print!("{}", output::render(&reports, format, time));
+    print_parse_errors(&reports);
+    check_failures(&reports)
+}
+
+fn check_all(dir: &Path, answers: Option<&Path>, jobs: usize) -> Result<(), Box<dyn Error>> {
+    let answers_path = answers.map_or_else(|| dir.join(ANSWERS_FILE), Path::to_path_buf);
+    let answers = Answers::load(&answers_path)
+        .map_err(|e| format!("Failed to load answers file {}: {}", answers_path.display(), e))?;
+    let reports = runner::run_all(dir, jobs);
+    let results = answers::check(&reports, &answers);
+    print!("{}", output::render_check(&results));
+    print_parse_errors(&reports);
+    let mismatches = results.iter()
+        .filter(|result| matches!(result.status, CheckStatus::Fail { .. }))
+        .count();
+    if mismatches > 0 {
+        return Err(From::from(format!("{} part(s) did not match {}", mismatches, answers_path.display())));
+    }
+    Ok(())
+}
+
+fn bench_day(
+    day_number: u32,
+    input: Option<&Path>,
+    options: &BenchOptions,
+    save_baseline: Option<&Path>,
+    baseline: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let day = find_day(day_number)?;
+    let source = resolve_source(day, input)?;
+    let text = source.read_to_string()
+        .map_err(|e| format!("Failed to read {}: {}", source, e))?;
+    let baseline = baseline
+        .map(|path| Baseline::load(path)
+            .map_err(|e| format!("Failed to load baseline {}: {}", path.display(), e)))
+        .transpose()?;
+    let report = bench::bench_day(day, &text, options)?;
+    print!("{}", output::render_bench(&report, baseline.as_ref()));
+    if let Some(path) = save_baseline {
+        Baseline::save(&report, path)
+            .map_err(|e| format!("Failed to write baseline {}: {}", path.display(), e))?;
+        println!("Saved baseline to {}", path.display());
+    }
+    Ok(())
+}
+
+fn render_watch(day: &Day, source: &Source, part: Option<u8>, answers_path: &Path) -> String {
+    let report = runner::run_day(day, source, part);
+    let mut screen = format!("Watching {} and {} (Ctrl-C to stop)\n\n", source, answers_path.display());
+    screen.push_str(&output::render_text_report(&report, true));
+    if let Some(snippet) = report.parse_error.as_ref().and_then(|e| output::render_snippet(&source.to_string(), e)) {
+        screen.push_str(&format!("\n{}", snippet));
+    }
+    if answers_path.exists() {
+        match Answers::load(answers_path) {
+            Ok(answers) => screen.push_str(&format!("\n{}", output::render_check(&answers::check(&[report], &answers)))),
+            Err(e) => screen.push_str(&format!("\nFailed to load answers file {}: {}\n", answers_path.display(), e)),
+        }
+    }
+    screen
+}
+
+fn watch_day(
+    day_number: u32,
+    input: Option<&Path>,
+    part: Option<u8>,
+    answers: Option<&Path>,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let day = find_day(day_number)?;
+    let source = resolve_source(day, input)?;
+    let input_path = match &source {
+        Source::File(path) => path.clone(),
+        _ => return Err(From::from("watch needs an input file, not stdin")),
     };
+    let answers_path = answers.map_or_else(|| Path::new(ANSWERS_FILE).to_path_buf(), Path::to_path_buf);
+    let mut watcher = Watcher::new(vec![input_path, answers_path.clone()]);
+    loop {
+        print!("\x1b[2J\x1b[H{}", render_watch(day, &source, part, &answers_path));
+        while !watcher.changed() {
+            thread::sleep(interval);
+        }
+    }
 }
 
+fn repl_day(day_number: u32, input: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let day = find_day(day_number)?;
+    let source = resolve_source(day, input)?;
+    let solution = match (day.parse)(&mut source.open()?) {
+        Ok(solution) => solution,
+        Err(e) => {
+            if let Some(snippet) = output::render_snippet(&source.to_string(), &e) {
+                eprint!("{}", snippet);
+            }
+            return Err(From::from(format!("Failed to parse {}: {}", source, e)));
+        }
+    };
+    println!("Loaded day {} ({}) from {}, type 'help' for commands", day.number, day.name, source);
+    repl::run(solution.as_ref(), &format!("day{}> ", day.number), io::stdin().lock(), io::stdout())?;
+    Ok(())
+}
+
+fn new_day(day_number: u32, name: &str) -> Result<(), Box<dyn Error>> {
+    for path in scaffold::scaffold(Path::new("."), day_number, name)? {
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn generate_input(day_number: u32, seed: u64, options: &GenOptions) -> Result<(), Box<dyn Error>> {
+    let day = find_day(day_number)?;
+    let generate = day.generate
+        .ok_or(format!("Day {} ({}) has no input generator", day.number, day.name))?;
+    print!("{}", generate(&mut Rng::new(seed), options));
+    Ok(())
+}
+
+fn crosscheck_days(day_number: Option<u32>, input: Option<&Path>, options: &CrosscheckOptions) -> Result<(), Box<dyn Error>> {
+    let reports = match (day_number, input) {
+        (Some(day_number), Some(input)) => {
+            let source = Source::from_arg(input);
+            vec![crosscheck::crosscheck_input(find_day(day_number)?, &source.to_string(), &source.read_to_string()?)?]
+        }
+        (Some(day_number), None) => crosscheck::crosscheck_generated(find_day(day_number)?, options)?,
+        (None, _) => {
+            let mut reports = Vec::new();
+            for day in DAYS.iter().filter(|day| day.generate.is_some()) {
+                reports.extend(crosscheck::crosscheck_generated(day, options)?);
+            }
+            reports
+        }
+    };
+    print!("{}", output::render_crosscheck(&reports));
+    let disagreements: usize = reports.iter().map(|report| report.disagreements().count()).sum();
+    if disagreements > 0 {
+        return Err(From::from(format!("{} part(s) disagree with the reference solver", disagreements)));
+    }
+    Ok(())
+}
+
+fn load_vocabulary(path: &Path) -> Result<Vocabulary, Box<dyn Error>> {
+    Vocabulary::load(path).map_err(|e| {
+        if let Some(snippet) = output::render_snippet(&path.display().to_string(), &e) {
+            eprint!("{}", snippet);
+        }
+        From::from(format!("Failed to load vocabulary {}: {}", path.display(), e))
+    })
+}
+
+fn calibrate(input: Option<&Path>, vocabulary: Option<&Path>, missing: MissingDigitPolicy, table: bool) -> Result<(), Box<dyn Error>> {
+    let source = resolve_source(find_day(1)?, input)?;
+    let mut trebuchet = Trebuchet::parse(source.open()?)?.with_missing_digits(missing);
+    if let Some(path) = vocabulary {
+        trebuchet = trebuchet.with_vocabulary(load_vocabulary(path)?);
+    }
+    if table {
+        print!("{}", output::render_calibration(&trebuchet.records(true).collect::<Vec<_>>()));
+    }
+    let calibration = trebuchet.calibrate(true).inspect_err(|e| {
+        if let Some(snippet) = output::render_snippet(&source.to_string(), e) {
+            eprint!("{}", snippet);
+        }
+    })?;
+    println!("Calibration sum: {} over {} line(s)", calibration.sum, calibration.counted);
+    if !calibration.missing.is_empty() {
+        let lines: Vec<String> = calibration.missing.iter().map(|line| line.to_string()).collect();
+        let handling = if missing == MissingDigitPolicy::Zero { "counted as 0" } else { "skipped" };
+        println!("Lines without a digit, {}: {}", handling, lines.join(", "));
+    }
+    Ok(())
+}
+
+fn list_days() {
+    for day in DAYS {
+        println!("{:>3}  {}", day.number, day.name);
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let result = match command {
+        Command::Run { day, input, part, time, format, trace } => {
+            trace::set_level(trace);
+            run_day(day, input.as_deref(), part, time, format)
+        }
+        Command::All { dir, time, format, jobs, trace } => {
+            trace::set_level(trace);
+            run_all(&dir, time, format, jobs.unwrap_or_else(runner::default_jobs))
+        }
+        Command::Check { dir, answers, jobs, trace } => {
+            trace::set_level(trace);
+            check_all(&dir, answers.as_deref(), jobs.unwrap_or_else(runner::default_jobs))
+        }
+        Command::Bench { day, input, iterations, warmup, save_baseline, baseline } => bench_day(
+            day,
+            input.as_deref(),
+            &BenchOptions { warmup, iterations },
+            save_baseline.as_deref(),
+            baseline.as_deref()),
+        Command::Watch { day, input, part, answers, interval, trace } => {
+            trace::set_level(trace);
+            watch_day(day, input.as_deref(), part, answers.as_deref(), interval)
+        }
+        Command::Repl { day, input } => repl_day(day, input.as_deref()),
+        Command::NewDay { day, name } => new_day(day, &name),
+        Command::Gen { day, seed, size, width } => generate_input(day, seed, &GenOptions { size, width }),
+        Command::Calibrate { input, vocabulary, missing, table } => {
+            calibrate(input.as_deref(), vocabulary.as_deref(), missing, table)
+        }
+        Command::Crosscheck { day, input, seed, runs, size, width } => crosscheck_days(
+            day,
+            input.as_deref(),
+            &CrosscheckOptions { seed, runs, generate: GenOptions { size, width } }),
+        Command::List => {
+            list_days();
+            Ok(())
+        }
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+// Aho-Corasick automaton over bytes, with failure links folded into a full
+// transition table so scanning never backtracks.
+struct Automaton {
+    transitions: Vec<[usize; 256]>,
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new(patterns: &[Vec<u8>]) -> Automaton {
+        let mut transitions = vec![[0; 256]];
+        let mut outputs = vec![Vec::new()];
+        for (index, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for &byte in pattern {
+                if transitions[state][byte as usize] == 0 {
+                    transitions.push([0; 256]);
+                    outputs.push(Vec::new());
+                    transitions[state][byte as usize] = transitions.len() - 1;
+                }
+                state = transitions[state][byte as usize];
+            }
+            outputs[state].push(index);
+        }
+
+        let mut failures = vec![0; transitions.len()];
+        let mut queue: VecDeque<usize> = transitions[0].iter().cloned().filter(|&state| state != 0).collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[failures[state]].clone();
+            outputs[state].extend(inherited);
+            let fallbacks = transitions[failures[state]];
+            for (byte, &fallback) in fallbacks.iter().enumerate() {
+                let next = transitions[state][byte];
+                if next == 0 {
+                    transitions[state][byte] = fallback;
+                } else {
+                    failures[next] = fallback;
+                    queue.push_back(next);
+                }
+            }
+        }
+        Automaton { transitions, outputs }
+    }
+
+    fn step(&self, state: usize, byte: u8) -> (usize, &[usize]) {
+        let next = self.transitions[state][byte as usize];
+        (next, &self.outputs[next])
+    }
+}
+
+pub struct Matcher {
+    patterns: Vec<String>,
+    forward: Automaton,
+    reverse: Automaton,
+    longest: usize,
+}
+
+impl Matcher {
+    pub fn new<S>(patterns: &[S]) -> Matcher
+    where S: AsRef<str> {
+        let patterns: Vec<String> = patterns.iter().map(|pattern| pattern.as_ref().to_string()).collect();
+        let forward: Vec<Vec<u8>> = patterns.iter().map(|pattern| pattern.as_bytes().to_vec()).collect();
+        let reverse: Vec<Vec<u8>> = patterns.iter().map(|pattern| pattern.bytes().rev().collect()).collect();
+        Matcher {
+            longest: patterns.iter().map(String::len).max().unwrap_or(0),
+            forward: Automaton::new(&forward),
+            reverse: Automaton::new(&reverse),
+            patterns,
+        }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    // The first match to end is not always the first to start when one
+    // pattern lies inside another, so keep scanning until no later match
+    // could start earlier. Ties go to the longest pattern.
+    pub fn first(&self, text: &str) -> Option<Match> {
+        let mut state = 0;
+        let mut best: Option<Match> = None;
+        for (index, &byte) in text.as_bytes().iter().enumerate() {
+            if best.is_some_and(|best| index >= best.start + self.longest) {
+                break;
+            }
+            let (next, outputs) = self.forward.step(state, byte);
+            state = next;
+            for &pattern in outputs {
+                let candidate = Match { pattern, start: index + 1 - self.patterns[pattern].len(), end: index + 1 };
+                if best.is_none_or(|best| (candidate.start, best.end) < (best.start, candidate.end)) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        best
+    }
+
+    // Scanning the reversed text, every match found at a byte starts there,
+    // so the first byte with a match holds the last match to start.
+    pub fn last(&self, text: &str) -> Option<Match> {
+        let mut state = 0;
+        for (index, &byte) in text.as_bytes().iter().enumerate().rev() {
+            let (next, outputs) = self.reverse.step(state, byte);
+            state = next;
+            let longest = outputs.iter()
+                .map(|&pattern| Match { pattern, start: index, end: index + self.patterns[pattern].len() })
+                .max_by_key(|candidate| candidate.end);
+            if longest.is_some() {
+                return longest;
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,308 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::answers::{CheckResult, CheckStatus};
+use crate::bench::{Baseline, BenchReport};
+use crate::crosscheck::{CrosscheckReport, ReferenceOutcome};
+use crate::error::Error;
+use crate::runner::DayReport;
+use crate::trebuchet::{CalibrationRecord, Token, TokenKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Format, String> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Invalid format '{}', expected text, json or csv", value)),
+        }
+    }
+}
+
+pub const CSV_HEADER: &str = "day,name,part,answer,error,parse_ns,elapsed_ns";
+
+fn format_elapsed(elapsed: Duration) -> String {
+    format!("{:.2?}", elapsed)
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn render(reports: &[DayReport], format: Format, time: bool) -> String {
+    match format {
+        Format::Text => render_text_table(reports, time),
+        Format::Json => render_json(reports),
+        Format::Csv => render_csv(reports),
+    }
+}
+
+pub fn render_text_report(report: &DayReport, time: bool) -> String {
+    let mut output = String::new();
+    for part in &report.parts {
+        match &part.outcome {
+            Ok(value) => output.push_str(&format!("Day {}, part {}: {}\n", report.day, part.part, value)),
+            Err(e) => output.push_str(&format!("Day {}, part {}: error: {}\n", report.day, part.part, e)),
+        }
+    }
+    for (label, value) in &report.diagnostics {
+        output.push_str(&format!("  {}: {}\n", label, value));
+    }
+    if time {
+        output.push_str(&format!("  parse time: {}\n", format_elapsed(report.parse_elapsed)));
+        for part in &report.parts {
+            output.push_str(&format!("  part {} time: {}\n", part.part, format_elapsed(part.elapsed)));
+        }
+    }
+    output
+}
+
+pub fn render_text_table(reports: &[DayReport], time: bool) -> String {
+    let mut output = String::new();
+    if time {
+        output.push_str(&format!("{:>3}  {:>4}  {:<20}  {:>12}  {:>12}\n", "Day", "Part", "Answer", "Parse", "Time"));
+    } else {
+        output.push_str(&format!("{:>3}  {:>4}  {:<20}  {:>12}\n", "Day", "Part", "Answer", "Time"));
+    }
+    for report in reports {
+        for part in &report.parts {
+            let (answer, error) = match &part.outcome {
+                Ok(value) => (value.to_string(), String::new()),
+                Err(e) => ("FAILED".to_string(), format!("  {}", e)),
+            };
+            if time {
+                output.push_str(&format!("{:>3}  {:>4}  {:<20}  {:>12}  {:>12}{}\n",
+                    report.day, part.part, answer,
+                    format_elapsed(report.parse_elapsed), format_elapsed(part.elapsed), error));
+            } else {
+                output.push_str(&format!("{:>3}  {:>4}  {:<20}  {:>12}{}\n",
+                    report.day, part.part, answer, format_elapsed(part.elapsed), error));
+            }
+        }
+    }
+    output
+}
+
+pub fn render_json(reports: &[DayReport]) -> String {
+    let rows: Vec<String> = reports
+        .iter()
+        .flat_map(|report| report.parts.iter().map(move |part| (report, part)))
+        .map(|(report, part)| {
+            let (answer, error) = match &part.outcome {
+                Ok(value) => (value.to_string(), "null".to_string()),
+                Err(e) => ("null".to_string(), json_string(e)),
+            };
+            format!(
+                "  {{\"day\": {}, \"name\": {}, \"part\": {}, \"answer\": {}, \"error\": {}, \"parse_ns\": {}, \"elapsed_ns\": {}}}",
+                report.day,
+                json_string(report.name),
+                part.part,
+                answer,
+                error,
+                report.parse_elapsed.as_nanos(),
+                part.elapsed.as_nanos())
+        })
+        .collect();
+    if rows.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+pub fn render_csv(reports: &[DayReport]) -> String {
+    let mut output = format!("{}\n", CSV_HEADER);
+    for report in reports {
+        for part in &report.parts {
+            let (answer, error) = match &part.outcome {
+                Ok(value) => (value.to_string(), String::new()),
+                Err(e) => (String::new(), csv_field(e)),
+            };
+            output.push_str(&format!("{},{},{},{},{},{},{}\n",
+                report.day,
+                csv_field(report.name),
+                part.part,
+                answer,
+                error,
+                report.parse_elapsed.as_nanos(),
+                part.elapsed.as_nanos()));
+        }
+    }
+    output
+}
+
+pub fn render_check(results: &[CheckResult]) -> String {
+    let mut output = format!("{:>3}  {:>4}  {:<7}  {:<20}  {}\n", "Day", "Part", "Status", "Expected", "Actual");
+    for result in results {
+        let actual = match &result.actual {
+            Ok(value) => value.to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        let (status, expected) = match &result.status {
+            CheckStatus::Pass => ("pass", actual.clone()),
+            CheckStatus::Fail { expected, .. } => ("FAIL", expected.to_string()),
+            CheckStatus::Missing => ("missing", "-".to_string()),
+        };
+        output.push_str(&format!("{:>3}  {:>4}  {:<7}  {:<20}  {}\n",
+            result.day, result.part, status, expected, actual));
+    }
+    output
+}
+
+pub fn render_bench(report: &BenchReport, baseline: Option<&Baseline>) -> String {
+    let samples = report.phases.first().map_or(0, |(_, stats)| stats.samples);
+    let mut output = format!("Day {} ({}), {} iterations\n", report.day, report.name, samples);
+    output.push_str(&format!("{:<6}  {:>12}  {:>12}  {:>12}  {:>12}", "Phase", "Min", "Median", "Mean", "Stddev"));
+    if baseline.is_some() {
+        output.push_str(&format!("  {:>12}  {:>8}", "Baseline", "Change"));
+    }
+    output.push('\n');
+    for (phase, stats) in &report.phases {
+        output.push_str(&format!("{:<6}  {:>12}  {:>12}  {:>12}  {:>12}",
+            phase,
+            format_elapsed(stats.min),
+            format_elapsed(stats.median),
+            format_elapsed(stats.mean),
+            format_elapsed(stats.stddev)));
+        if let Some(baseline) = baseline {
+            let median = baseline.median(report.day, phase)
+                .map_or("-".to_string(), format_elapsed);
+            let change = baseline.change(report.day, phase, stats.median)
+                .map_or("-".to_string(), |change| format!("{:+.1}%", change));
+            output.push_str(&format!("  {:>12}  {:>8}", median, change));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn format_outcome(outcome: &Result<u64, String>) -> String {
+    match outcome {
+        Ok(value) => value.to_string(),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+pub fn render_crosscheck(reports: &[CrosscheckReport]) -> String {
+    let mut output = format!("{:>3}  {:>4}  {:>6}  {:>6}  {}\n", "Day", "Part", "Inputs", "Agree", "Status");
+    let mut rows: Vec<(u32, u8, usize, usize, usize, bool)> = Vec::new();
+    for report in reports {
+        for part in &report.parts {
+            let row = match rows.iter_mut().find(|row| row.0 == report.day && row.1 == part.part) {
+                Some(row) => row,
+                None => {
+                    rows.push((report.day, part.part, 0, 0, 0, part.reference != ReferenceOutcome::Missing));
+                    rows.last_mut().expect("row was just pushed")
+                }
+            };
+            row.2 += 1;
+            match &part.reference {
+                ReferenceOutcome::Solved(_) if part.agrees() => row.3 += 1,
+                ReferenceOutcome::Skipped(_) => row.4 += 1,
+                _ => {}
+            }
+        }
+    }
+    for (day, part, inputs, agree, skipped, has_reference) in rows {
+        let status = if !has_reference {
+            "no reference".to_string()
+        } else if agree + skipped < inputs {
+            "DISAGREE".to_string()
+        } else if skipped == inputs {
+            "skipped".to_string()
+        } else if skipped > 0 {
+            format!("ok, {} skipped", skipped)
+        } else {
+            "ok".to_string()
+        };
+        let agree = if has_reference { agree.to_string() } else { "-".to_string() };
+        output.push_str(&format!("{:>3}  {:>4}  {:>6}  {:>6}  {}\n", day, part, inputs, agree, status));
+    }
+    for report in reports {
+        for part in &report.parts {
+            match &part.reference {
+                ReferenceOutcome::Solved(reference) if !part.agrees() => {
+                    output.push_str(&format!("Day {}, part {}, {}: optimized {}, reference {}\n",
+                        report.day, part.part, report.source, format_outcome(&part.optimized), format_outcome(reference)));
+                }
+                ReferenceOutcome::Skipped(message) => {
+                    output.push_str(&format!("Day {}, part {}, {}: reference skipped: {}\n",
+                        report.day, part.part, report.source, message));
+                }
+                _ => {}
+            }
+        }
+    }
+    output
+}
+
+fn calibration_token(token: Option<&Token>) -> String {
+    match token {
+        Some(token) => {
+            let kind = match token.kind {
+                TokenKind::Digit => "digit",
+                TokenKind::Word => "word",
+            };
+            format!("{:<8}  {:<5}  {:>4}  {:>4}", token.text, kind, token.byte_offset, token.char_offset)
+        }
+        None => format!("{:<8}  {:<5}  {:>4}  {:>4}", "-", "-", "-", "-"),
+    }
+}
+
+pub fn render_calibration(records: &[CalibrationRecord]) -> String {
+    let mut output = format!("{:>4}  {:>5}  {:<8}  {:<5}  {:>4}  {:>4}  {:<8}  {:<5}  {:>4}  {:>4}\n",
+        "Line", "Value", "First", "Kind", "Byte", "Char", "Last", "Kind", "Byte", "Char");
+    for record in records {
+        let value = record.value().map_or("-".to_string(), |value| format!("{:02}", value));
+        output.push_str(&format!("{:>4}  {:>5}  {}  {}\n",
+            record.line, value, calibration_token(record.first.as_ref()), calibration_token(record.last.as_ref())));
+    }
+    output
+}
+
+pub fn render_snippet(origin: &str, error: &Error) -> Option<String> {
+    let (line, column, text, message, line_text) = match error {
+        Error::Parse { line, column, text, message, line_text } if *line > 0 => (line, column, text, message, line_text),
+        _ => return None,
+    };
+    let prefix = line_text.get(..column.saturating_sub(1)).unwrap_or(line_text);
+    let padding: String = prefix.chars().map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+    let carets = "^".repeat(text.chars().count().max(1));
+    let gutter = " ".repeat(line.to_string().len());
+    let mut output = String::new();
+    output.push_str(&format!("{}--> {}:{}:{}\n", gutter, origin, line, column));
+    output.push_str(&format!("{} |\n", gutter));
+    output.push_str(&format!("{} | {}\n", line, line_text));
+    output.push_str(&format!("{} | {}{} {}\n", gutter, padding, carets, message));
+    Some(output)
+}
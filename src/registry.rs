@@ -0,0 +1,53 @@
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::generate::{GenOptions, Rng};
+use crate::solution::Solution;
+use crate::trebuchet::{self, Trebuchet};
+use crate::cube_conundrum::{self, CubeConundrum};
+use crate::gear_ratios::{self, GearRatios};
+use crate::scratchcards::{self, Scratchcards};
+use crate::food_production::{self, FoodProduction};
+use crate::boat_race::{self, BoatRace};
+use crate::camel_cards::{self, CamelCards};
+use crate::haunted_wasteland::{self, HauntedWasteland};
+
+pub type ParseFn = fn(&mut dyn BufRead) -> Result<Box<dyn Solution>>;
+pub type GenerateFn = fn(&mut Rng, &GenOptions) -> String;
+
+pub struct Day {
+    pub number: u32,
+    pub name: &'static str,
+    pub parse: ParseFn,
+    pub generate: Option<GenerateFn>,
+}
+
+impl Day {
+    pub fn input_path(&self, dir: &Path) -> Option<PathBuf> {
+        [format!("input_{}", self.number), format!("input_{}_short", self.number)]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+}
+
+fn parse_boxed<S>(reader: &mut dyn BufRead) -> Result<Box<dyn Solution>>
+where S: Solution + 'static {
+    Ok(Box::new(S::parse(reader)?))
+}
+
+pub static DAYS: &[Day] = &[
+    Day { number: 1, name: "trebuchet", parse: parse_boxed::<Trebuchet>, generate: Some(trebuchet::generate) },
+    Day { number: 2, name: "cube_conundrum", parse: parse_boxed::<CubeConundrum>, generate: Some(cube_conundrum::generate) },
+    Day { number: 3, name: "gear_ratios", parse: parse_boxed::<GearRatios>, generate: Some(gear_ratios::generate) },
+    Day { number: 4, name: "scratchcards", parse: parse_boxed::<Scratchcards>, generate: Some(scratchcards::generate) },
+    Day { number: 5, name: "food_production", parse: parse_boxed::<FoodProduction>, generate: Some(food_production::generate) },
+    Day { number: 6, name: "boat_race", parse: parse_boxed::<BoatRace>, generate: Some(boat_race::generate) },
+    Day { number: 7, name: "camel_cards", parse: parse_boxed::<CamelCards>, generate: Some(camel_cards::generate) },
+    Day { number: 8, name: "haunted_wasteland", parse: parse_boxed::<HauntedWasteland>, generate: Some(haunted_wasteland::generate) },
+];
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -0,0 +1,68 @@
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+const BUILTINS: &[(&str, &str)] = &[
+    ("part1", "solve part one"),
+    ("part2", "solve part two"),
+    ("help", "list the available commands"),
+    ("quit", "leave the REPL"),
+];
+
+pub fn argument<T: FromStr>(args: &[&str], index: usize, name: &str) -> Result<T> {
+    let value = args
+        .get(index)
+        .ok_or_else(|| Error::invalid_query(format!("missing <{}>", name)))?;
+    value.parse::<T>()
+        .map_err(|_| Error::invalid_query(format!("invalid <{}> '{}'", name, value)))
+}
+
+pub fn unknown_command(command: &str) -> Error {
+    Error::invalid_query(format!("unknown command '{}', try 'help'", command))
+}
+
+fn help(solution: &dyn Solution) -> String {
+    solution.queries()
+        .iter()
+        .chain(BUILTINS)
+        .map(|(usage, description)| format!("  {:<24} {}\n", usage, description))
+        .collect()
+}
+
+pub fn evaluate(solution: &dyn Solution, line: &str) -> Option<String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (command, args) = match words.split_first() {
+        Some((command, args)) => (*command, args),
+        None => return Some(String::new()),
+    };
+    let result = match command {
+        "quit" | "exit" => return None,
+        "help" => Ok(help(solution)),
+        "part1" => solution.part_one().map(|answer| format!("{}\n", answer)),
+        "part2" => solution.part_two().map(|answer| format!("{}\n", answer)),
+        _ => solution.query(command, args).map(|reply| format!("{}\n", reply)),
+    };
+    Some(result.unwrap_or_else(|e| format!("error: {}\n", e)))
+}
+
+pub fn run<R, W>(solution: &dyn Solution, prompt: &str, input: R, mut output: W) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut lines = input.lines();
+    loop {
+        write!(output, "{}", prompt)?;
+        output.flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return writeln!(output),
+        };
+        match evaluate(solution, &line) {
+            Some(reply) => write!(output, "{}", reply)?,
+            None => return Ok(()),
+        }
+    }
+}
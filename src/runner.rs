@@ -0,0 +1,163 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::input::Source;
+use crate::registry::{Day, DAYS};
+use crate::solution::Solution;
+use crate::trace;
+
+pub struct PartReport {
+    pub part: u8,
+    pub outcome: std::result::Result<u64, String>,
+    pub elapsed: Duration,
+}
+
+pub struct DayReport {
+    pub day: u32,
+    pub name: &'static str,
+    pub input: Option<String>,
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartReport>,
+    pub diagnostics: Vec<(&'static str, String)>,
+    pub parse_error: Option<Error>,
+}
+
+impl DayReport {
+    pub fn failures(&self) -> usize {
+        self.parts.iter().filter(|part| part.outcome.is_err()).count()
+    }
+
+    pub fn total_elapsed(&self) -> Duration {
+        self.parse_elapsed + self.parts.iter().map(|part| part.elapsed).sum::<Duration>()
+    }
+}
+
+fn selected_parts(part: Option<u8>) -> Vec<u8> {
+    match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    }
+}
+
+fn time_part<F>(part: u8, solve: F) -> PartReport
+where F: FnOnce() -> Result<u64> {
+    let start = Instant::now();
+    let outcome = solve().map_err(|e| e.to_string());
+    PartReport { part, outcome, elapsed: start.elapsed() }
+}
+
+fn failed_parts(message: &str, part: Option<u8>) -> Vec<PartReport> {
+    selected_parts(part)
+        .into_iter()
+        .map(|part| PartReport {
+            part,
+            outcome: Err(message.to_string()),
+            elapsed: Duration::ZERO,
+        })
+        .collect()
+}
+
+fn solve_parts(solution: &dyn Solution, part: Option<u8>) -> Vec<PartReport> {
+    selected_parts(part)
+        .into_iter()
+        .map(|part| match part {
+            1 => time_part(1, || solution.part_one()),
+            _ => time_part(2, || solution.part_two()),
+        })
+        .collect()
+}
+
+fn parse_source(day: &Day, source: &Source) -> Result<Box<dyn Solution>> {
+    let mut reader = source.open()?;
+    (day.parse)(&mut reader)
+}
+
+pub fn run_day(day: &Day, source: &Source, part: Option<u8>) -> DayReport {
+    trace::set_day(Some(day.number));
+    let start = Instant::now();
+    let parsed = parse_source(day, source);
+    let parse_elapsed = start.elapsed();
+    let (parts, diagnostics, parse_error) = match parsed {
+        Ok(solution) => (solve_parts(solution.as_ref(), part), solution.diagnostics(), None),
+        Err(e) => (failed_parts(&format!("Failed to parse {}: {}", source, e), part), Vec::new(), Some(e)),
+    };
+    DayReport {
+        day: day.number,
+        name: day.name,
+        input: Some(source.to_string()),
+        parse_elapsed,
+        parts,
+        diagnostics,
+        parse_error,
+    }
+}
+
+fn failed_report(day: &Day, input: Option<String>, message: &str) -> DayReport {
+    DayReport {
+        day: day.number,
+        name: day.name,
+        input,
+        parse_elapsed: Duration::ZERO,
+        parts: failed_parts(message, None),
+        diagnostics: Vec::new(),
+        parse_error: None,
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload.downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+fn run_discovered(day: &Day, dir: &Path) -> DayReport {
+    let path = match day.input_path(dir) {
+        Some(path) => path,
+        None => return failed_report(day, None, &format!("No input for day {} in {}", day.number, dir.display())),
+    };
+    let source = Source::File(path);
+    panic::catch_unwind(AssertUnwindSafe(|| run_day(day, &source, None)))
+        .unwrap_or_else(|payload| {
+            let message = format!("Panicked while solving {}: {}", source, panic_message(payload.as_ref()));
+            failed_report(day, Some(source.to_string()), &message)
+        })
+}
+
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |jobs| jobs.get())
+}
+
+pub fn run_all(dir: &Path, jobs: usize) -> Vec<DayReport> {
+    run_days(DAYS, dir, jobs)
+}
+
+pub fn run_days(days: &[Day], dir: &Path, jobs: usize) -> Vec<DayReport> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days.len().max(1)) {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(day) = days.get(index) else { break };
+                    if sender.send((index, run_discovered(day, dir))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    drop(sender);
+    let mut reports: Vec<(usize, DayReport)> = receiver.into_iter().collect();
+    reports.sort_by_key(|&(index, _)| index);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
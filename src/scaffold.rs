@@ -0,0 +1,154 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::registry;
+
+const MODULE_TEMPLATE: &str = "\
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::input;
+use crate::solution::{Answer, Solution};
+
+pub struct {type} {
+    lines: Vec<String>,
+}
+
+impl Solution for {type} {
+    fn parse<R: BufRead>(reader: R) -> Result<{type}> {
+        let lines = input::lines(reader).collect::<std::io::Result<Vec<String>>>()?;
+        Ok({type} { lines })
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        Err(Error::unsolvable(format!(\"part one is not implemented yet ({} lines)\", self.lines.len())))
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        Err(Error::unsolvable(format!(\"part two is not implemented yet ({} lines)\", self.lines.len())))
+    }
+}
+
+pub fn run_day_{number}<P>(path: P) -> Result<Answer>
+where P: AsRef<Path> {
+    Ok({type}::parse_file(path.as_ref())?.solve())
+}
+";
+
+const TEST_TEMPLATE: &str = "\
+use std::path::Path;
+
+use advent_of_code_2023::{name}::{type};
+use advent_of_code_2023::solution::Solution;
+
+#[test]
+fn test_parse_example() {
+    assert!({type}::parse_file(Path::new(\"input_{number}_short\")).is_ok());
+}
+";
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(io::Error::new(ErrorKind::InvalidData, message))
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some('a'..='z'))
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !name.ends_with('_')
+}
+
+pub fn type_name(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        })
+        .collect()
+}
+
+fn render(template: &str, number: u32, name: &str) -> String {
+    template
+        .replace("{type}", &type_name(name))
+        .replace("{name}", name)
+        .replace("{number}", &number.to_string())
+}
+
+pub fn module_source(number: u32, name: &str) -> String {
+    render(MODULE_TEMPLATE, number, name)
+}
+
+pub fn test_source(number: u32, name: &str) -> String {
+    render(TEST_TEMPLATE, number, name)
+}
+
+pub fn register_module(lib_rs: &str, name: &str) -> Option<String> {
+    let anchor = lib_rs.find("pub mod solution;")?;
+    Some(format!("{}pub mod {};\n{}", &lib_rs[..anchor], name, &lib_rs[anchor..]))
+}
+
+pub fn register_day(registry_rs: &str, number: u32, name: &str) -> Option<String> {
+    let type_name = type_name(name);
+    let mut lines: Vec<String> = registry_rs.lines().map(String::from).collect();
+
+    let last_import = lines.iter().rposition(|line| line.starts_with("use crate::"))?;
+    lines.insert(last_import + 1, format!("use crate::{}::{};", name, type_name));
+
+    let days_start = lines.iter().position(|line| line.starts_with("pub static DAYS"))?;
+    let days_end = days_start + lines[days_start..].iter().position(|line| line.starts_with("];"))?;
+    let position = lines[days_start + 1..days_end]
+        .iter()
+        .position(|line| {
+            line.split("number: ")
+                .nth(1)
+                .and_then(|rest| rest.split(',').next())
+                .and_then(|entry| entry.parse::<u32>().ok())
+                .is_some_and(|entry| entry > number)
+        })
+        .map_or(days_end, |offset| days_start + 1 + offset);
+    lines.insert(position, format!(
+        "    Day {{ number: {}, name: \"{}\", parse: parse_boxed::<{}>, generate: None }},", number, name, type_name));
+
+    Some(lines.join("\n") + "\n")
+}
+
+
+pub fn scaffold(root: &Path, number: u32, name: &str) -> Result<Vec<PathBuf>> {
+    if !is_valid_name(name) {
+        return Err(invalid_data(format!("invalid module name '{}', expected snake_case", name)));
+    }
+    if let Some(day) = registry::find_day(number) {
+        return Err(invalid_data(format!("day {} is already registered as {}", number, day.name)));
+    }
+
+    let lib_path = root.join("src").join("lib.rs");
+    let registry_path = root.join("src").join("registry.rs");
+    let lib_rs = fs::read_to_string(&lib_path)?;
+    if lib_rs.lines().any(|line| line.trim() == format!("pub mod {};", name)) {
+        return Err(invalid_data(format!("module '{}' is already declared in {}", name, lib_path.display())));
+    }
+    let lib_rs = register_module(&lib_rs, name)
+        .ok_or_else(|| invalid_data(format!("no 'pub mod solution;' line in {}", lib_path.display())))?;
+    let registry_rs = register_day(&fs::read_to_string(&registry_path)?, number, name)
+        .ok_or_else(|| invalid_data(format!("no imports or DAYS table found in {}", registry_path.display())))?;
+
+    let module_path = root.join("src").join(format!("{}.rs", name));
+    let test_path = root.join("tests").join(format!("{}_tests.rs", name));
+    let input_path = root.join(format!("input_{}_short", number));
+    if let Some(existing) = [&module_path, &test_path, &input_path].into_iter().find(|path| path.exists()) {
+        return Err(Error::Io(io::Error::new(ErrorKind::AlreadyExists,
+            format!("{} already exists", existing.display()))));
+    }
+
+    fs::write(&module_path, module_source(number, name))?;
+    fs::write(&test_path, test_source(number, name))?;
+    fs::write(&input_path, "")?;
+    fs::write(&lib_path, lib_rs)?;
+    fs::write(&registry_path, registry_rs)?;
+
+    Ok(vec![module_path, test_path, input_path, lib_path, registry_path])
+}
@@ -1,12 +1,16 @@
 use std::collections::HashMap;
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
+use crate::error::{Error, Result};
+use crate::generate::{GenOptions, Rng};
+use crate::input::{self, fields, parse_field};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+
 #[derive(Clone)]
 pub struct Card {
-    _id: String,
+    id: String,
     winning_numbers: Vec<u32>,
     card_numbers: Vec<u32>,
 }
@@ -14,90 +18,216 @@ pub struct Card {
 impl Card {
     fn new(id: &str, winning_numbers: Vec<u32>, card_numbers: Vec<u32>) -> Card {
         Card {
-            _id: id.to_string(),
+            id: id.to_string(),
             winning_numbers,
             card_numbers,
         }
     }
 
-    fn from_string(input: &str) -> Result<Card, Box<dyn std::error::Error>> {
-        let parts: Vec::<&str> = input.split(":").collect();
-        let (id, numbers_string) = (parts[0], parts[1]);
-        let parts: Vec::<&str> = numbers_string.split("|").collect();
-        let winning_numbers = parts[0]
-            .split_whitespace()
-            .map(|x| x.parse::<u32>())
-            .collect::<Result<Vec<u32>, _>>()?;
-        let card_numbers = parts[1]
-            .split_whitespace()
-            .map(|x| x.parse::<u32>())
-            .collect::<Result<Vec<_>, _>>()?;
+    pub fn from_string(input: &str) -> Result<Card> {
+        let (id, numbers_string) = input.split_once(':')
+            .ok_or_else(|| Error::parse(input.len() + 1, "", "expected ':' after the card id"))?;
+        let numbers_offset = id.len() + 1;
+        let (winning_string, card_string) = numbers_string.split_once('|')
+            .ok_or_else(|| Error::parse(input.len() + 1, "", "expected '|' between winning and card numbers"))?;
+        let card_offset = numbers_offset + winning_string.len() + 1;
+        let winning_numbers = fields(winning_string)
+            .into_iter()
+            .map(|(offset, x)| parse_field::<u32>(numbers_offset + offset, x))
+            .collect::<Result<Vec<u32>>>()?;
+        let card_numbers = fields(card_string)
+            .into_iter()
+            .map(|(offset, x)| parse_field::<u32>(card_offset + offset, x))
+            .collect::<Result<Vec<_>>>()?;
         Ok(Card::new(id, winning_numbers, card_numbers))
     }
 
-    fn get_num_matches(&self) -> u32 {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn winning_numbers(&self) -> &[u32] {
+        &self.winning_numbers
+    }
+
+    pub fn card_numbers(&self) -> &[u32] {
+        &self.card_numbers
+    }
+
+    pub fn get_num_matches(&self) -> u32 {
         let mut matches = 0;
         for number in &self.winning_numbers {
             if self.card_numbers.contains(number) {
                 matches += 1;
             }
         }
-        return matches;
+        matches
     }
 
-    fn get_score(&self) -> u32 {
+    pub fn get_score(&self) -> Result<u64> {
         let matches = self.get_num_matches();
         if matches > 0
         {
-            return 2u32.pow(matches - 1);
+            return 2u64.checked_pow(matches - 1)
+                .ok_or_else(|| Error::unsolvable(format!("{}: score for {} matches overflows u64", self.id, matches)));
         }
-        return 0;
+        Ok(0)
     }
 }
 
 pub fn get_num_copies(
     cards: &Vec::<Card>, 
     index: usize, cache: 
-    &mut HashMap<usize, u32>
-) -> u32 {
+    &mut HashMap<usize, u64>
+) -> Result<u64> {
     if let Some(&num_copies) = cache.get(&index) {
-        num_copies
+        Ok(num_copies)
     } else {
         let num_matches = cards[index].get_num_matches();
-        let mut num_copies = 1;
-        for i in 1..(num_matches + 1) {
-            num_copies += get_num_copies(cards, index + i as usize, cache);
+        let mut num_copies: u64 = 1;
+        // A card cannot win copies of cards past the end of the table.
+        for next in index + 1..(index + 1 + num_matches as usize).min(cards.len()) {
+            num_copies = num_copies.checked_add(get_num_copies(cards, next, cache)?)
+                .ok_or_else(|| Error::unsolvable(format!("{}: number of copies overflows u64", cards[index].id)))?;
         }
+        crate::trace!(1, "{}: {} winning number(s), {} card(s) including won copies",
+            cards[index].id, num_matches, num_copies);
         cache.insert(index, num_copies);
-        num_copies
+        Ok(num_copies)
+    }
+}
+
+pub struct Scratchcards {
+    cards: Vec<Card>,
+}
+
+impl Scratchcards {
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
+impl Solution for Scratchcards {
+    fn parse<R: BufRead>(reader: R) -> Result<Scratchcards> {
+        let cards = input::lines(reader)
+            .enumerate()
+            .map(|(line_index, line)| -> Result<Card>{
+                let line = line?;
+                let card = Card::from_string(&line).map_err(|e| e.at_line(line_index + 1, &line))?;
+                Ok(card)
+            })
+            .collect::<Result<Vec<Card>>>()?;
+        Ok(Scratchcards { cards })
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        let mut total: u64 = 0;
+        for card in &self.cards {
+            let score = card.get_score()?;
+            crate::trace!(1, "{}: {} winning number(s), score {}", card.id, card.get_num_matches(), score);
+            total = total.checked_add(score)
+                .ok_or_else(|| Error::unsolvable("total score overflows u64"))?;
+        }
+        Ok(total)
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        let mut num_copies: u64 = 0;
+        let mut cache: HashMap<usize, u64> = HashMap::new();
+        for i in 0..self.cards.len() {
+            num_copies = num_copies.checked_add(get_num_copies(&self.cards, i, &mut cache)?)
+                .ok_or_else(|| Error::unsolvable("total number of cards overflows u64"))?;
+        }
+        Ok(num_copies)
+    }
+
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        let total = self.cards
+            .iter()
+            .map(|card| {
+                let mut score = 0;
+                for number in &card.card_numbers {
+                    if card.winning_numbers.contains(number) {
+                        score = if score == 0 { 1 } else { score * 2 };
+                    }
+                }
+                score
+            })
+            .sum::<u64>();
+        Some(Ok(total))
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        let mut copies: Vec<u64> = vec![1; self.cards.len()];
+        for (index, card) in self.cards.iter().enumerate() {
+            let matches = card.card_numbers.iter()
+                .filter(|number| card.winning_numbers.contains(number))
+                .count();
+            for next in index + 1..(index + 1 + matches).min(self.cards.len()) {
+                copies[next] += copies[index];
+            }
+        }
+        Some(Ok(copies.iter().sum()))
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[("card <n>", "winning numbers, score and copies won by card n")]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "card" => {
+                let number: usize = argument(args, 0, "n")?;
+                let index = number.checked_sub(1)
+                    .filter(|&index| index < self.cards.len())
+                    .ok_or_else(|| Error::invalid_query(format!("no card {}", number)))?;
+                let card = &self.cards[index];
+                let matching: Vec<u32> = card.winning_numbers.iter()
+                    .filter(|number| card.card_numbers.contains(number))
+                    .cloned()
+                    .collect();
+                let copies = get_num_copies(&self.cards, index, &mut HashMap::new())?;
+                Ok(format!("{}: winning numbers {:?}, score {}, {} card(s) including won copies",
+                    card.id, matching, card.get_score()?, copies))
+            }
+            _ => Err(repl::unknown_command(command)),
+        }
+    }
+}
+
+const MAX_GENERATED_COPIES: u64 = 1_000_000;
+
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    let winning_count = options.width.clamp(1, 28);
+    let card_count = winning_count * 5 / 2;
+    // Copies compound quickly, so pick match counts from the last card backwards
+    // and keep every card's copy count bounded. A card may not win copies of
+    // cards past the end of the table.
+    let mut matches = vec![0; options.size];
+    let mut copies: Vec<u64> = vec![1; options.size];
+    for index in (0..options.size).rev() {
+        let remaining = options.size - index - 1;
+        let mut count = rng.range(0..winning_count + 1).min(remaining);
+        while count > 0 && 1 + copies[index + 1..=index + count].iter().sum::<u64>() > MAX_GENERATED_COPIES {
+            count -= 1;
+        }
+        matches[index] = count;
+        copies[index] = 1 + copies[index + 1..=index + count].iter().sum::<u64>();
+    }
+    let join = |numbers: &[usize]| numbers.iter().map(|n| format!("{:>2}", n)).collect::<Vec<_>>().join(" ");
+    let mut output = String::new();
+    for (index, &matches) in matches.iter().enumerate() {
+        let mut pool: Vec<usize> = (1..100).collect();
+        rng.shuffle(&mut pool);
+        let (winning, others) = pool.split_at(winning_count);
+        let mut card: Vec<usize> = winning[..matches].iter().chain(&others[..card_count - matches]).cloned().collect();
+        rng.shuffle(&mut card);
+        output.push_str(&format!("Card {:>3}: {} | {}\n", index + 1, join(winning), join(&card)));
     }
+    output
 }
 
-pub fn run_day_4<P>(path: P) -> Result<(), Box<dyn Error>> 
+pub fn run_day_4<P>(path: P) -> Result<Answer> 
 where P: AsRef<Path> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let cards = reader
-        .lines()
-        .map(|line| -> Result<Card, Box<dyn Error>>{
-            let line = line?;
-            let card = Card::from_string(&line)?;
-            Ok(card)
-        })
-        .collect::<Result<Vec<Card>, Box<dyn Error>>>()?;
-    
-    let total = cards
-        .iter()
-        .map(|card| card.get_score())
-        .sum::<u32>();
-    
-    println!("Total score: {}", total);
-
-    let mut num_copies = 0;
-    let mut cache: HashMap<usize, u32> = HashMap::new();
-    for i in 0..cards.len() {
-        num_copies += get_num_copies(&cards, i, &mut cache);
-    }
-    println!("Total copies: {}", num_copies);
-    Ok(())
+    Ok(Scratchcards::parse_file(path.as_ref())?.solve())
 }
\ No newline at end of file
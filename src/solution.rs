@@ -0,0 +1,77 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::repl;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub part_one: Option<u64>,
+    pub part_two: Option<u64>,
+    pub diagnostics: Vec<(&'static str, String)>,
+}
+
+pub trait Solution {
+    fn parse<R: BufRead>(reader: R) -> Result<Self>
+    where
+        Self: Sized;
+
+    fn parse_str(input: &str) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::parse(input.as_bytes())
+    }
+
+    fn parse_file(path: &Path) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::parse(BufReader::new(File::open(path)?))
+    }
+
+    fn part_one(&self) -> Result<u64>;
+
+    fn part_two(&self) -> Result<u64>;
+
+    // Slow but straightforward solvers used to cross-check the optimized parts.
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        None
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        None
+    }
+
+    fn diagnostics(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    fn query(&self, command: &str, _args: &[&str]) -> Result<String> {
+        Err(repl::unknown_command(command))
+    }
+
+    fn solve(&self) -> Answer {
+        let mut diagnostics = self.diagnostics();
+        let part_one = match self.part_one() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                diagnostics.push(("part_one_error", e.to_string()));
+                None
+            }
+        };
+        let part_two = match self.part_two() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                diagnostics.push(("part_two_error", e.to_string()));
+                None
+            }
+        };
+        Answer { part_one, part_two, diagnostics }
+    }
+}
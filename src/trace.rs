@@ -0,0 +1,63 @@
+use std::cell::Cell;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+pub const MAX_LEVEL: u8 = 2;
+
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+thread_local! {
+    static DAY: Cell<Option<u32>> = const { Cell::new(None) };
+    static SILENCED: Cell<bool> = const { Cell::new(false) };
+}
+
+// Restores the previous state even if the silenced code panics.
+struct SilenceGuard(bool);
+
+impl Drop for SilenceGuard {
+    fn drop(&mut self) {
+        SILENCED.with(|silenced| silenced.set(self.0));
+    }
+}
+
+pub fn set_level(level: u8) {
+    LEVEL.store(level.min(MAX_LEVEL), Ordering::Relaxed);
+}
+
+pub fn level() -> u8 {
+    LEVEL.load(Ordering::Relaxed)
+}
+
+pub fn enabled(level: u8) -> bool {
+    level > 0 && level <= self::level() && !SILENCED.with(Cell::get)
+}
+
+// Runs `f` without tracing on the current thread, e.g. when a solver is
+// called again only to compute diagnostics.
+pub fn silenced<T, F>(f: F) -> T
+where F: FnOnce() -> T {
+    let _guard = SilenceGuard(SILENCED.with(|silenced| silenced.replace(true)));
+    f()
+}
+
+pub fn set_day(day: Option<u32>) {
+    DAY.with(|current| current.set(day));
+}
+
+pub fn emit(args: fmt::Arguments) {
+    let line = match DAY.with(Cell::get) {
+        Some(day) => format!("trace: day {}: {}\n", day, args),
+        None => format!("trace: {}\n", args),
+    };
+    let _ = io::stderr().lock().write_all(line.as_bytes());
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::trace::enabled($level) {
+            $crate::trace::emit(format_args!($($arg)*));
+        }
+    };
+}
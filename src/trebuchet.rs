@@ -1,81 +1,392 @@
-use std::collections::HashMap;
-use std::error::Error;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
-fn calculate_sum_of_first_last_digits<P>(path: P) -> Result<u32, Box<dyn Error>>
-where P: AsRef<Path> {
-    let file = File::open(&path)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    let mut sum = 0;
-    while let Some(line) = lines.next() {
-        let line = line?;
-        let digits: Vec<u32> = line.chars()
-            .filter_map(|c| c.to_digit(10))
-            .collect();
-        if let (Some(&first), Some(&last)) = (digits.first(), digits.last()) {
-            sum += first * 10 + last;
+use crate::error::{Error, Result};
+use crate::generate::{GenOptions, Rng};
+use crate::input::{self, fields, parse_field};
+use crate::matcher::{Match, Matcher};
+use crate::repl::{self, argument};
+use crate::solution::{Answer, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingDigitPolicy {
+    Skip,
+    Zero,
+    Error,
+}
+
+impl FromStr for MissingDigitPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<MissingDigitPolicy, String> {
+        match value {
+            "skip" => Ok(MissingDigitPolicy::Skip),
+            "zero" => Ok(MissingDigitPolicy::Zero),
+            "error" => Ok(MissingDigitPolicy::Error),
+            _ => Err(format!("Invalid policy '{}', expected skip, zero or error", value)),
         }
     }
-    Ok(sum)
 }
 
-fn calculate_sum_of_first_and_last_numbers<P>(path: P) -> Result<u32, Box<dyn Error>>
-where P: AsRef<Path> {
-    let file = File::open(&path)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    let literals: HashMap<&str, u32> = [
-        ("one", 1),
-        ("two", 2),
-        ("three", 3),
-        ("four", 4),
-        ("five", 5),
-        ("six", 6),
-        ("seven", 7),
-        ("eight", 8),
-        ("nine", 9),
-        ("1", 1),
-        ("2", 2),
-        ("3", 3),
-        ("4", 4),
-        ("5", 5),
-        ("6", 6),
-        ("7", 7),
-        ("8", 8),
-        ("9", 9),
-    ].iter().cloned().collect();
-
-    let mut sum = 0;
-    while let Some(line) = lines.next() {
-        let line = line?;
-        let mut numbers: Vec<(usize, u32)> = Vec::new();
-        for (index, _) in line.char_indices() {
-            for (&literal, &value) in literals.iter() {
-                if line[index..].starts_with(literal) {
-                    numbers.push((index, value));
-                    break;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calibration {
+    pub sum: u64,
+    pub counted: usize,
+    pub missing: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Digit,
+    Word,
+}
+
+// Offsets are zero-based, counted in bytes and in chars from the start of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub kind: TokenKind,
+    pub value: u32,
+    pub byte_offset: usize,
+    pub char_offset: usize,
+}
+
+impl<'a> Token<'a> {
+    fn new(line: &'a str, start: usize, end: usize, kind: TokenKind, value: u32) -> Token<'a> {
+        Token {
+            text: &line[start..end],
+            kind,
+            value,
+            byte_offset: start,
+            char_offset: line[..start].chars().count(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationRecord<'a> {
+    pub line: usize,
+    pub text: &'a str,
+    pub first: Option<Token<'a>>,
+    pub last: Option<Token<'a>>,
+}
+
+impl CalibrationRecord<'_> {
+    pub fn value(&self) -> Option<u32> {
+        Some(self.first?.value * 10 + self.last?.value)
+    }
+}
+
+fn missing_digit_error(line_number: usize, line: &str, words: bool) -> Error {
+    let message = if words { "no digit or digit word in line" } else { "no digit in line" };
+    Error::parse(1, line, message).at_line(line_number, line)
+}
+
+pub struct Trebuchet {
+    lines: Vec<String>,
+    vocabulary: Vocabulary,
+    missing_digits: MissingDigitPolicy,
+}
+
+impl Trebuchet {
+    pub fn new(lines: Vec<String>) -> Trebuchet {
+        Trebuchet { lines, vocabulary: Vocabulary::english(), missing_digits: MissingDigitPolicy::Skip }
+    }
+
+    pub fn with_vocabulary(self, vocabulary: Vocabulary) -> Trebuchet {
+        Trebuchet { vocabulary, ..self }
+    }
+
+    pub fn with_missing_digits(self, missing_digits: MissingDigitPolicy) -> Trebuchet {
+        Trebuchet { missing_digits, ..self }
+    }
+
+    pub fn missing_digits(&self) -> MissingDigitPolicy {
+        self.missing_digits
+    }
+
+    fn record<'a>(&self, index: usize, line: &'a str, words: bool) -> CalibrationRecord<'a> {
+        let (first, last) = if words {
+            let token = |found: Match| {
+                let kind = if found.pattern < DIGIT_LITERALS { TokenKind::Digit } else { TokenKind::Word };
+                Token::new(line, found.start, found.end, kind, self.vocabulary.literals[found.pattern].1)
+            };
+            (self.vocabulary.matcher.first(line).map(token), self.vocabulary.matcher.last(line).map(token))
+        } else {
+            let digit = |(start, c): (usize, char)| c.to_digit(10).map(|value| Token::new(line, start, start + 1, TokenKind::Digit, value));
+            (line.char_indices().find_map(digit), line.char_indices().rev().find_map(digit))
+        };
+        CalibrationRecord { line: index + 1, text: line, first, last }
+    }
+
+    // One record per input line, with digit words when `words` is set.
+    pub fn records(&self, words: bool) -> impl Iterator<Item = CalibrationRecord<'_>> {
+        self.lines.iter().enumerate().map(move |(index, line)| self.record(index, line, words))
+    }
+
+    // Sums the calibration values of every line, with digit words when
+    // `words` is set. Lines without a value are listed in `missing` and
+    // handled by the missing digit policy.
+    pub fn calibrate(&self, words: bool) -> Result<Calibration> {
+        let mut calibration = Calibration { sum: 0, counted: 0, missing: Vec::new() };
+        for record in self.records(words) {
+            let value = record.value();
+            crate::trace!(1, "line {} '{}': calibration value {:?}", record.line, record.text, value);
+            match (value, self.missing_digits) {
+                (Some(value), _) => {
+                    calibration.sum += value as u64;
+                    calibration.counted += 1;
+                }
+                (None, MissingDigitPolicy::Error) => return Err(missing_digit_error(record.line, record.text, words)),
+                (None, policy) => {
+                    calibration.missing.push(record.line);
+                    if policy == MissingDigitPolicy::Zero {
+                        calibration.counted += 1;
+                    }
+                }
+            }
+        }
+        Ok(calibration)
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn vocabulary(&self) -> &Vocabulary {
+        &self.vocabulary
+    }
+}
+
+pub fn first_last_digits(line: &str) -> Option<u32> {
+    let digits: Vec<u32> = line.chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect();
+    match (digits.first(), digits.last()) {
+        (Some(&first), Some(&last)) => Some(first * 10 + last),
+        _ => None,
+    }
+}
+
+fn describe_line(line: &str, vocabulary: &Vocabulary) -> String {
+    let digits = first_last_digits(line).map_or("none".to_string(), |value| value.to_string());
+    let words = vocabulary.calibration_value(line).map_or("none".to_string(), |value| value.to_string());
+    format!("'{}': digits {}, with words {}", line, digits, words)
+}
+
+const DIGIT_WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+// Every vocabulary starts with the digits 1 to 9, followed by its words.
+const DIGIT_LITERALS: usize = 9;
+
+fn digit_literals(digits: std::ops::Range<u32>) -> Vec<(String, u32)> {
+    digits.map(|digit| (digit.to_string(), digit)).collect()
+}
+
+// Digits 1 to 9 always count, so words are checked against them too. Words
+// may overlap at their ends, like "eightwo", but a word inside another with
+// a different value would make the first or last digit ambiguous.
+fn literal_collision(literals: &[(String, u32)], word: &str, value: u32) -> Option<String> {
+    if word.is_empty() {
+        return Some("words must not be empty".to_string());
+    }
+    if value > 9 {
+        return Some(format!("value {} of '{}' is not a single digit", value, word));
+    }
+    literals.iter().find_map(|(other, other_value)| {
+        if other == word {
+            Some(format!("'{}' is defined more than once", word))
+        } else if *other_value != value && (other.contains(word) || word.contains(other.as_str())) {
+            Some(format!("'{}' ({}) and '{}' ({}) overlap with different values", word, value, other, other_value))
+        } else {
+            None
+        }
+    })
+}
+
+pub struct Vocabulary {
+    literals: Vec<(String, u32)>,
+    matcher: Matcher,
+}
+
+impl Vocabulary {
+    fn build(literals: Vec<(String, u32)>) -> Vocabulary {
+        let patterns: Vec<&str> = literals.iter().map(|(literal, _)| literal.as_str()).collect();
+        Vocabulary { matcher: Matcher::new(&patterns), literals }
+    }
+
+    pub fn new<S>(words: &[(S, u32)]) -> Result<Vocabulary>
+    where S: AsRef<str> {
+        let mut literals = digit_literals(1..DIGIT_LITERALS as u32 + 1);
+        for (word, value) in words {
+            if let Some(message) = literal_collision(&literals, word.as_ref(), *value) {
+                return Err(Error::invalid_vocabulary(message));
+            }
+            literals.push((word.as_ref().to_string(), *value));
+        }
+        Ok(Vocabulary::build(literals))
+    }
+
+    pub fn english() -> Vocabulary {
+        let words: Vec<(&str, u32)> = DIGIT_WORDS.iter().cloned().zip(1..).collect();
+        Vocabulary::new(&words).expect("the English digit words do not collide")
+    }
+
+    fn parse_line(line: &str, literals: &[(String, u32)]) -> Result<Option<(String, u32)>> {
+        let line = line.split('#').next().unwrap_or("");
+        match fields(line).as_slice() {
+            [] => Ok(None),
+            &[(word_offset, word), (value_offset, value)] => {
+                let value = parse_field::<u32>(value_offset, value)?;
+                match literal_collision(literals, word, value) {
+                    Some(message) => Err(Error::parse(word_offset + 1, word, message)),
+                    None => Ok(Some((word.to_string(), value))),
                 }
             }
+            _ => Err(Error::parse(1, line.trim(), format!("expected '<word> <digit>', got '{}'", line.trim()))),
+        }
+    }
+
+    pub fn parse<R: BufRead>(reader: R) -> Result<Vocabulary> {
+        let mut literals = digit_literals(1..DIGIT_LITERALS as u32 + 1);
+        for (line_index, line) in input::lines(reader).enumerate() {
+            let line = line?;
+            if let Some(literal) = Self::parse_line(&line, &literals).map_err(|e| e.at_line(line_index + 1, &line))? {
+                literals.push(literal);
+            }
         }
+        Ok(Vocabulary::build(literals))
+    }
+
+    pub fn load(path: &Path) -> Result<Vocabulary> {
+        Vocabulary::parse(BufReader::new(File::open(path)?))
+    }
 
-        numbers.sort_unstable_by_key(|&(index, _)| index);
-        
-        let first_value = numbers.first().map(|&(_, value)| value).unwrap_or(0);
-        let last_value = numbers.last().map(|&(_, value)| value).unwrap_or(0);
+    pub fn words(&self) -> &[(String, u32)] {
+        &self.literals[DIGIT_LITERALS..]
+    }
 
-        sum += first_value * 10 + last_value;
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        let first = self.matcher.first(line)?;
+        let last = self.matcher.last(line)?;
+        Some(self.literals[first.pattern].1 * 10 + self.literals[last.pattern].1)
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Vocabulary {
+        Vocabulary::english()
+    }
+}
+
+pub fn first_last_numbers(line: &str) -> u32 {
+    static ENGLISH: OnceLock<Vocabulary> = OnceLock::new();
+    ENGLISH.get_or_init(Vocabulary::english).calibration_value(line).unwrap_or(0)
+}
+
+fn reference_calibration(line: &str, literals: &[(String, u32)]) -> Option<u32> {
+    let bytes = line.as_bytes();
+    let value_at = |index: usize| literals.iter()
+        .find(|(literal, _)| bytes[index..].starts_with(literal.as_bytes()))
+        .map(|&(_, value)| value);
+    let first = (0..bytes.len()).find_map(value_at);
+    let last = (0..bytes.len()).rev().find_map(value_at);
+    first.zip(last).map(|(first, last)| first * 10 + last)
+}
+
+impl Trebuchet {
+    fn reference_sum(&self, literals: &[(String, u32)], words: bool) -> Result<u64> {
+        let mut sum = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            match reference_calibration(line, literals) {
+                Some(value) => sum += value as u64,
+                None if self.missing_digits == MissingDigitPolicy::Error => return Err(missing_digit_error(index + 1, line, words)),
+                None => {}
+            }
+        }
+        Ok(sum)
+    }
+}
+
+impl Solution for Trebuchet {
+    fn parse<R: BufRead>(reader: R) -> Result<Trebuchet> {
+        let lines = input::lines(reader).collect::<std::io::Result<Vec<String>>>()?;
+        Ok(Trebuchet::new(lines))
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        Ok(self.calibrate(false)?.sum)
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        Ok(self.calibrate(true)?.sum)
+    }
+
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        Some(self.reference_sum(&digit_literals(0..10), false))
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        Some(self.reference_sum(&self.vocabulary.literals, true))
+    }
+
+    fn diagnostics(&self) -> Vec<(&'static str, String)> {
+        let mut diagnostics = Vec::new();
+        for (label, words) in [("lines_without_digits", false), ("lines_without_digits_or_words", true)] {
+            let missing: Vec<usize> = self.records(words)
+                .filter(|record| record.value().is_none())
+                .map(|record| record.line)
+                .collect();
+            if !missing.is_empty() {
+                diagnostics.push((label, format!("{:?}", missing)));
+            }
+        }
+        diagnostics
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("line <n>", "calibration values of input line n"),
+            ("value <text>", "calibration values of any text"),
+        ]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "line" => {
+                let number: usize = argument(args, 0, "n")?;
+                let line = number.checked_sub(1)
+                    .and_then(|index| self.lines.get(index))
+                    .ok_or_else(|| Error::invalid_query(format!("no line {}", number)))?;
+                Ok(describe_line(line, &self.vocabulary))
+            }
+            "value" => Ok(describe_line(&args.join(" "), &self.vocabulary)),
+            _ => Err(repl::unknown_command(command)),
+        }
+    }
+}
+
+pub fn generate(rng: &mut Rng, options: &GenOptions) -> String {
+    let tokens = options.width.max(1);
+    let mut output = String::new();
+    for _ in 0..options.size {
+        let digit_at = rng.range(0..tokens);
+        for position in 0..tokens {
+            match rng.range(0..4) {
+                _ if position == digit_at => output.push_str(&rng.range(1..10).to_string()),
+                0 => output.push_str(rng.choose::<&str>(&DIGIT_WORDS)),
+                _ => output.push((b'a' + rng.range(0..26) as u8) as char),
+            }
+        }
+        output.push('\n');
     }
-    Ok(sum)
+    output
 }
 
-pub fn run_day_1<P>(path: P) -> Result<(), Box<dyn Error>> 
+pub fn run_day_1<P>(path: P) -> Result<Answer> 
 where P: AsRef<Path> {
-    let sum1 = calculate_sum_of_first_last_digits(&path)?;
-    println!("Day 1, part 1: {}", sum1);
-    let sum2 = calculate_sum_of_first_and_last_numbers(&path)?;
-    println!("Day 1, part 2: {}", sum2);
-    Ok(())
+    Ok(Trebuchet::parse_file(path.as_ref())?.solve())
 }
\ No newline at end of file
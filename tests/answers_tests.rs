@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use advent_of_code_2023::answers::{check, Answers, CheckStatus};
+use advent_of_code_2023::runner::{DayReport, PartReport};
+
+fn report(day: u32, outcomes: Vec<Result<u64, String>>) -> DayReport {
+    DayReport {
+        day,
+        name: "test",
+        input: None,
+        parse_elapsed: Duration::ZERO,
+        parts: outcomes
+            .into_iter()
+            .enumerate()
+            .map(|(index, outcome)| PartReport { part: index as u8 + 1, outcome, elapsed: Duration::ZERO })
+            .collect(),
+        diagnostics: Vec::new(),
+        parse_error: None,
+    }
+}
+
+#[test]
+fn test_check() {
+    let answers = Answers::parse("# day part answer\n1 1 142\n1 2 281\n\n2 1 8  # trailing comment\n".as_bytes()).unwrap();
+    let reports = vec![
+        report(1, vec![Ok(142), Ok(142)]),
+        report(2, vec![Err("broken".to_string()), Ok(2286)]),
+    ];
+    let statuses: Vec<CheckStatus> = check(&reports, &answers)
+        .into_iter()
+        .map(|result| result.status)
+        .collect();
+    assert_eq!(statuses, vec![
+        CheckStatus::Pass,
+        CheckStatus::Fail { expected: 281, actual: Ok(142) },
+        CheckStatus::Fail { expected: 8, actual: Err("broken".to_string()) },
+        CheckStatus::Missing,
+    ]);
+}
+
+#[test]
+fn test_parse_errors() {
+    let test_cases = vec![
+        ("1 1", "line 1, column 1: expected '<day> <part> <answer>', got '1 1'"),
+        ("1 3 5", "line 1, column 3: invalid part '3'"),
+        ("1 1 5\n1 1 6", "line 2, column 1: duplicate answer for day 1 part 1"),
+        ("1 1 x", "line 1, column 5: invalid number 'x': invalid digit found in string"),
+    ];
+
+    for (input, expected) in test_cases {
+        let error = Answers::parse(input.as_bytes()).err().unwrap();
+        assert_eq!(error.to_string(), expected);
+    }
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use advent_of_code_2023::bench::{Baseline, Stats};
+
+#[test]
+fn test_stats_from_samples() {
+    let samples: Vec<Duration> = [40, 10, 30, 20].iter().map(|&n| Duration::from_nanos(n)).collect();
+    let stats = Stats::from_samples(&samples).unwrap();
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.min, Duration::from_nanos(10));
+    assert_eq!(stats.median, Duration::from_nanos(25));
+    assert_eq!(stats.mean, Duration::from_nanos(25));
+    assert_eq!(stats.stddev, Duration::from_nanos(11));
+    assert_eq!(Stats::from_samples(&[]), None);
+}
+
+#[test]
+fn test_baseline_change() {
+    let baseline = Baseline::parse("5 parse 1000\n5 part1 200\n".as_bytes()).unwrap();
+    assert_eq!(baseline.median(5, "parse"), Some(Duration::from_nanos(1000)));
+    assert_eq!(baseline.change(5, "parse", Duration::from_nanos(1500)), Some(50.0));
+    assert_eq!(baseline.change(5, "part1", Duration::from_nanos(150)), Some(-25.0));
+    assert_eq!(baseline.change(5, "part2", Duration::from_nanos(150)), None);
+    assert!(Baseline::parse("5 parse".as_bytes()).is_err());
+}
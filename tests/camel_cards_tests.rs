@@ -1,18 +1,19 @@
-use advent_of_code_2023::camel_cards::{Hand, HandRank};
+use advent_of_code_2023::camel_cards::{Hand, HandJoker, HandRank};
+use advent_of_code_2023::error::Error;
 
 #[test]
 fn test_hand_rank() {
     let test_cases = vec![
         ("AAAAA 100", HandRank::FiveOfAKind('A')),
-        ("AAAA1 100", HandRank::FourOfAKind('A')),
-        ("AAA11 100", HandRank::FullHouse('A', '1')),
-        ("AA111 100", HandRank::FullHouse('1', 'A')),
-        ("A1111 100", HandRank::FourOfAKind('1')),
-        ("11111 100", HandRank::FiveOfAKind('1')),
-        ("1111A 100", HandRank::FourOfAKind('1')),
-        ("111AA 100", HandRank::FullHouse('1', 'A')),
-        ("11AAA 100", HandRank::FullHouse('A', '1')),
-        ("1AAAA 100", HandRank::FourOfAKind('A')),
+        ("AAAAK 100", HandRank::FourOfAKind('A')),
+        ("AAAKK 100", HandRank::FullHouse('A', 'K')),
+        ("AAKKK 100", HandRank::FullHouse('K', 'A')),
+        ("AKKKK 100", HandRank::FourOfAKind('K')),
+        ("KKKKK 100", HandRank::FiveOfAKind('K')),
+        ("KKKKA 100", HandRank::FourOfAKind('K')),
+        ("KKKAA 100", HandRank::FullHouse('K', 'A')),
+        ("KKAAA 100", HandRank::FullHouse('A', 'K')),
+        ("KAAAA 100", HandRank::FourOfAKind('A')),
         ("23456 100", HandRank::HighCard),
         ("22345 100", HandRank::OnePair('2')),
         ("22344 100", HandRank::TwoPairs('4', '2')),
@@ -31,3 +32,9 @@ fn test_hand_rank() {
     }
 
 }
+
+#[test]
+fn test_invalid_card_labels() {
+    assert!(matches!(Hand::from_string("AAAA1 100"), Err(Error::Parse { column: 5, .. })));
+    assert!(matches!(HandJoker::from_string("AAAA1 100"), Err(Error::Parse { column: 5, .. })));
+}
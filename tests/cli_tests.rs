@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use advent_of_code_2023::cli::{parse_args, Command};
+use advent_of_code_2023::output::Format;
+use advent_of_code_2023::trebuchet::MissingDigitPolicy;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn test_parse_args() {
+    let test_cases = vec![
+        ("run 3 input_3_short", Command::Run { day: 3, input: Some(PathBuf::from("input_3_short")), part: None, time: false, format: Format::Text, trace: 0 }),
+        ("run 7 --part 2 --trace 2", Command::Run { day: 7, input: None, part: Some(2), time: false, format: Format::Text, trace: 2 }),
+        ("run 5 -i input -p 1 -t", Command::Run { day: 5, input: Some(PathBuf::from("input")), part: Some(1), time: true, format: Format::Text, trace: 0 }),
+        ("1 input_1_short", Command::Run { day: 1, input: Some(PathBuf::from("input_1_short")), part: None, time: false, format: Format::Text, trace: 0 }),
+        ("all", Command::All { dir: PathBuf::from("."), time: false, format: Format::Text, jobs: None, trace: 0 }),
+        ("all inputs --time -j 2", Command::All { dir: PathBuf::from("inputs"), time: true, format: Format::Text, jobs: Some(2), trace: 0 }),
+        ("all -f json", Command::All { dir: PathBuf::from("."), time: false, format: Format::Json, jobs: None, trace: 0 }),
+        ("run 6 --format csv", Command::Run { day: 6, input: None, part: None, time: false, format: Format::Csv, trace: 0 }),
+        ("bench 5 -n 3 --baseline base", Command::Bench {
+            day: 5,
+            input: None,
+            iterations: 3,
+            warmup: 2,
+            save_baseline: None,
+            baseline: Some(PathBuf::from("base")),
+        }),
+        ("watch 8 input_8_short -a answers_short --interval 100", Command::Watch {
+            day: 8,
+            input: Some(PathBuf::from("input_8_short")),
+            part: None,
+            answers: Some(PathBuf::from("answers_short")),
+            interval: Duration::from_millis(100),
+            trace: 0,
+        }),
+        ("repl 5 input_5_short", Command::Repl { day: 5, input: Some(PathBuf::from("input_5_short")) }),
+        ("new-day 9 mirage_maintenance", Command::NewDay { day: 9, name: "mirage_maintenance".to_string() }),
+        ("gen 5 --seed 1 --size 3 --width 2", Command::Gen { day: 5, seed: 1, size: 3, width: 2 }),
+        ("gen 4", Command::Gen { day: 4, seed: 2023, size: 100, width: 10 }),
+        ("crosscheck", Command::Crosscheck { day: None, input: None, seed: 2023, runs: 10, size: 4, width: 4 }),
+        ("crosscheck 6 input_6_short --runs 3", Command::Crosscheck {
+            day: Some(6),
+            input: Some(PathBuf::from("input_6_short")),
+            seed: 2023,
+            runs: 3,
+            size: 4,
+            width: 4,
+        }),
+        ("calibrate input_1_short --vocabulary words", Command::Calibrate {
+            input: Some(PathBuf::from("input_1_short")),
+            vocabulary: Some(PathBuf::from("words")),
+            missing: MissingDigitPolicy::Skip,
+            table: false,
+        }),
+        ("calibrate --missing error --table", Command::Calibrate {
+            input: None,
+            vocabulary: None,
+            missing: MissingDigitPolicy::Error,
+            table: true,
+        }),
+        ("list", Command::List),
+        ("run 2 --help", Command::Help),
+        ("--help", Command::Help),
+    ];
+
+    for (input, expected) in test_cases {
+        assert_eq!(parse_args(&args(input)), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_parse_args_errors() {
+    let test_cases = vec![
+        ("", "Missing command"),
+        ("run", "Missing day for run"),
+        ("run one", "Invalid day 'one', expected a number"),
+        ("run 1 --part 3", "Invalid part '3', expected 1 or 2"),
+        ("run 1 --input", "Missing value for --input"),
+        ("run 1 a b", "Unexpected argument 'b' for run"),
+        ("all --part 1", "--part is only supported by run and watch"),
+        ("watch 1 --interval 0", "--interval must be at least 1"),
+        ("check --jobs 0", "--jobs must be at least 1"),
+        ("new-day 9 MirageMaintenance", "Invalid module name 'MirageMaintenance', expected snake_case"),
+        ("repl 8 -", "repl reads queries from stdin, pass an input file"),
+        ("run 2 --trace 3", "Invalid trace level '3', expected 0 to 2"),
+        ("all --format xml", "Invalid format 'xml', expected text, json or csv"),
+        ("bench 5 --iterations 0", "--iterations must be at least 1"),
+        ("bench 5 --warmup many", "Invalid value 'many' for --warmup, expected a number"),
+        ("gen", "Missing day for gen"),
+        ("crosscheck --runs 0", "--runs must be at least 1"),
+        ("crosscheck --input input_6_short", "crosscheck needs a day to check an input"),
+        ("calibrate --missing never", "Invalid policy 'never', expected skip, zero or error"),
+        ("frobnicate", "Unknown command 'frobnicate'"),
+        ("list --verbose", "Unknown option '--verbose'"),
+        ("list --time", "--time is only supported by run and all"),
+        ("run 1 --vocabulary words --missing error", "--vocabulary is only supported by calibrate"),
+        ("1 input_1 --table", "--table is only supported by calibrate"),
+        ("bench 5 -f json", "--format is only supported by run, all, check and watch"),
+        ("gen 5 --trace 2", "--trace is only supported by run, all, check and watch"),
+        ("new-day 9 mirage --input x", "--input is not supported by new-day"),
+    ];
+
+    for (input, expected) in test_cases {
+        let error = parse_args(&args(input)).unwrap_err();
+        assert_eq!(error.to_string(), expected, "{}", input);
+    }
+}
@@ -0,0 +1,114 @@
+use std::fs;
+use std::io::BufRead;
+
+use advent_of_code_2023::crosscheck::{crosscheck_generated, crosscheck_input, crosscheck_solution, CrosscheckOptions, CrosscheckReport, ReferenceOutcome};
+use advent_of_code_2023::error::{Error, Result};
+use advent_of_code_2023::generate::GenOptions;
+use advent_of_code_2023::output::render_crosscheck;
+use advent_of_code_2023::registry::{find_day, DAYS};
+use advent_of_code_2023::solution::Solution;
+
+#[test]
+fn test_reference_solvers_agree() {
+    let options = CrosscheckOptions { seed: 7, runs: 5, generate: GenOptions { size: 3, width: 3 } };
+    for day in DAYS.iter().filter(|day| day.generate.is_some()) {
+        let example = fs::read_to_string(format!("input_{}_short", day.number)).unwrap();
+        let mut reports = crosscheck_generated(day, &options).unwrap();
+        reports.push(crosscheck_input(day, "example", &example).unwrap());
+        for report in &reports {
+            if let Some(part) = report.disagreements().next() {
+                panic!("day {} part {} on {}: optimized {:?}, reference {:?}",
+                    report.day, part.part, report.source, part.optimized, part.reference);
+            }
+        }
+    }
+}
+
+// Agrees with its reference on part one and not on part two.
+struct Disagreeing;
+
+impl Solution for Disagreeing {
+    fn parse<R: BufRead>(_reader: R) -> Result<Disagreeing> {
+        Ok(Disagreeing)
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        Ok(1)
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        Ok(2)
+    }
+
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        Some(Ok(1))
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        Some(Ok(3))
+    }
+}
+
+#[test]
+fn test_crosscheck_reports_disagreements() {
+    let parts = crosscheck_solution(&Disagreeing);
+    let report = CrosscheckReport { day: 4, name: "disagreeing", source: "stub".to_string(), parts };
+    let disagreements: Vec<u8> = report.disagreements().map(|part| part.part).collect();
+    assert_eq!(disagreements, vec![2]);
+    assert_eq!(report.parts[1].optimized, Ok(2));
+    assert_eq!(report.parts[1].reference, ReferenceOutcome::Solved(Ok(3)));
+
+    let rendered = render_crosscheck(&[report]);
+    assert!(rendered.contains("  4     1       1       1  ok\n"), "{}", rendered);
+    assert!(rendered.contains("  4     2       1       0  DISAGREE\n"), "{}", rendered);
+    assert!(rendered.contains("Day 4, part 2, stub: optimized 2, reference 3\n"), "{}", rendered);
+}
+
+// Gives up on part one and agrees on part two.
+struct Skipping;
+
+impl Solution for Skipping {
+    fn parse<R: BufRead>(_reader: R) -> Result<Skipping> {
+        Ok(Skipping)
+    }
+
+    fn part_one(&self) -> Result<u64> {
+        Ok(1)
+    }
+
+    fn part_two(&self) -> Result<u64> {
+        Ok(2)
+    }
+
+    fn reference_part_one(&self) -> Option<Result<u64>> {
+        Some(Err(Error::skipped("too many steps")))
+    }
+
+    fn reference_part_two(&self) -> Option<Result<u64>> {
+        Some(Ok(2))
+    }
+}
+
+#[test]
+fn test_crosscheck_skipped_references_are_not_disagreements() {
+    let reports: Vec<CrosscheckReport> = ["first", "second"].iter()
+        .zip([crosscheck_solution(&Skipping), crosscheck_solution(&Disagreeing)])
+        .map(|(source, parts)| CrosscheckReport { day: 8, name: "stub", source: source.to_string(), parts })
+        .collect();
+    assert_eq!(reports[0].parts[0].reference, ReferenceOutcome::Skipped("too many steps".to_string()));
+    assert_eq!(reports[0].disagreements().count(), 0);
+
+    let rendered = render_crosscheck(&reports[..1]);
+    assert!(rendered.contains("  8     1       1       0  skipped\n"), "{}", rendered);
+    assert!(rendered.contains("  8     2       1       1  ok\n"), "{}", rendered);
+    assert!(rendered.contains("Day 8, part 1, first: reference skipped: too many steps\n"), "{}", rendered);
+
+    let rendered = render_crosscheck(&reports);
+    assert!(rendered.contains("  8     1       2       1  ok, 1 skipped\n"), "{}", rendered);
+    assert!(rendered.contains("  8     2       2       1  DISAGREE\n"), "{}", rendered);
+
+    let almanac = fs::read_to_string("input_5_short").unwrap().replacen("seeds: 79 14", "seeds: 79 200000000", 1);
+    let report = crosscheck_input(find_day(5).unwrap(), "many seeds", &almanac).unwrap();
+    assert!(matches!(report.parts[1].reference, ReferenceOutcome::Skipped(_)));
+    assert_eq!(report.disagreements().count(), 0);
+}
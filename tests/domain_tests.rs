@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use advent_of_code_2023::boat_race::{record_hold_interval, BoatRace};
+use advent_of_code_2023::cube_conundrum::{Color, CubeGame};
+use advent_of_code_2023::food_production::FoodProduction;
+use advent_of_code_2023::gear_ratios::GearRatios;
+use advent_of_code_2023::haunted_wasteland::{HauntedWasteland, Instruction};
+use advent_of_code_2023::scratchcards::Card;
+use advent_of_code_2023::solution::Solution;
+use advent_of_code_2023::trebuchet::{first_last_digits, first_last_numbers};
+
+#[test]
+fn test_trebuchet_lines() {
+    assert_eq!(first_last_digits("pqr3stu8vwx"), Some(38));
+    assert_eq!(first_last_digits("trebuchet"), None);
+    assert_eq!(first_last_numbers("xtwone3four"), 24);
+}
+
+#[test]
+fn test_cube_game() {
+    let game = CubeGame::from_string("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green").unwrap();
+    assert_eq!(game.index(), 3);
+    assert_eq!(game.cube_sets().len(), 2);
+    assert_eq!(game.cube_sets()[0].count(Color::Red), 20);
+    assert!(!game.is_valid(12, 13, 14));
+    assert_eq!(game.cube_power(), 20 * 13 * 6);
+}
+
+#[test]
+fn test_gear_ratios_parts_near() {
+    let gear_ratios = GearRatios::parse_str("467..114..\n...*......\n..35..633.\n").unwrap();
+    let gear = &gear_ratios.symbols()[0];
+    assert_eq!(gear.value(), '*');
+    let ids: Vec<i32> = gear_ratios.parts_near(gear).iter().map(|part| part.id()).collect();
+    assert_eq!(ids, vec![467, 35]);
+}
+
+#[test]
+fn test_card_matches() {
+    let card = Card::from_string("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
+    assert_eq!(card.winning_numbers(), &[41, 48, 83, 86, 17]);
+    assert_eq!(card.get_num_matches(), 4);
+    assert_eq!(card.get_score().unwrap(), 8);
+}
+
+#[test]
+fn test_almanac_location() {
+    let food_production = FoodProduction::parse_file(Path::new("input_5_short")).unwrap();
+    assert_eq!(food_production.seeds(), &[79, 14, 55, 13]);
+    assert_eq!(food_production.almanac().get_location(79).unwrap(), 82);
+}
+
+#[test]
+fn test_boat_races() {
+    let boat_race = BoatRace::parse_str("Time: 7 15\nDistance: 9 40\n").unwrap();
+    assert_eq!(boat_race.races(), vec![(7, 9), (15, 40)]);
+    assert_eq!(boat_race.race(), (715, 940));
+    assert_eq!(record_hold_interval(7, 9).unwrap(), (1, 6));
+}
+
+#[test]
+fn test_wasteland_next() {
+    let wasteland = HauntedWasteland::parse_str("LR\n\nAAA = (BBB, CCC)\nBBB = (AAA, ZZZ)\n").unwrap();
+    assert_eq!(wasteland.instructions(), &[Instruction::Left, Instruction::Right]);
+    assert_eq!(wasteland.next("AAA", Instruction::Right), Some("CCC"));
+    assert_eq!(wasteland.next("CCC", Instruction::Left), None);
+}
+
+#[test]
+fn test_wasteland_part_one_follows_names() {
+    // CCC has the same neighbours as ZZZ but is not the end.
+    let wasteland = HauntedWasteland::parse_str(
+        "L\n\nAAA = (BBB, BBB)\nBBB = (CCC, CCC)\nCCC = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap();
+    assert_eq!(wasteland.part_one().unwrap(), 3);
+}
@@ -0,0 +1,127 @@
+use advent_of_code_2023::boat_race::BoatRace;
+use advent_of_code_2023::camel_cards::CamelCards;
+use advent_of_code_2023::cube_conundrum::CubeConundrum;
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::food_production::FoodProduction;
+use advent_of_code_2023::gear_ratios::GearRatios;
+use advent_of_code_2023::haunted_wasteland::HauntedWasteland;
+use advent_of_code_2023::scratchcards::Scratchcards;
+use advent_of_code_2023::solution::Solution;
+
+fn parse_position<S: Solution>(input: &str) -> (usize, usize) {
+    match S::parse_str(input) {
+        Err(Error::Parse { line, column, .. }) => (line, column),
+        Err(e) => panic!("expected a parse error, got {}", e),
+        Ok(_) => panic!("expected a parse error for {:?}", input),
+    }
+}
+
+#[test]
+fn test_parse_error_positions() {
+    assert_eq!(parse_position::<CubeConundrum>("Game 1: 3 blue\nGame 2: 3 blue, 4 rde"), (2, 19));
+    assert_eq!(parse_position::<CubeConundrum>("Game x: 3 blue"), (1, 6));
+    assert_eq!(parse_position::<Scratchcards>("Card 1: 41 4x | 83"), (1, 12));
+    assert_eq!(parse_position::<Scratchcards>("Card 1: 41 48 83"), (1, 17));
+    assert_eq!(parse_position::<FoodProduction>("seeds: 79 14\n\nseed-to-soil map:\n50 98"), (4, 1));
+    assert_eq!(parse_position::<FoodProduction>("seeds: 79 1x"), (1, 11));
+    assert_eq!(parse_position::<BoatRace>("Time: 7 15\nDistance 9 40"), (2, 14));
+    assert_eq!(parse_position::<CamelCards>("32T3K 765\nT55X5 684"), (2, 4));
+    assert_eq!(parse_position::<CamelCards>("32T3K 7x5"), (1, 7));
+    assert_eq!(parse_position::<HauntedWasteland>("LRX\n\nAAA = (BBB, CCC)"), (1, 3));
+    assert_eq!(parse_position::<HauntedWasteland>("LR\n\nAAA = (BBB"), (3, 11));
+    assert_eq!(parse_position::<HauntedWasteland>("LR\n\nAAA - [ZZZ; ZZZ]"), (3, 5));
+    assert_eq!(parse_position::<HauntedWasteland>("LR\n\nAAAA = (BBB, CCC)"), (3, 4));
+    assert_eq!(parse_position::<HauntedWasteland>("LR\n\nAAA = (BBB, CCC))"), (3, 17));
+    assert_eq!(parse_position::<GearRatios>("467..\n...*12345678901"), (2, 5));
+}
+
+#[test]
+fn test_missing_sections() {
+    assert!(matches!(BoatRace::parse_str("Time: 7 15 30\n"), Err(Error::MissingSection(section)) if section == "Distance"));
+    assert!(matches!(HauntedWasteland::parse_str(""), Err(Error::MissingSection(section)) if section == "instructions"));
+
+    let food_production = FoodProduction::parse_str("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n").unwrap();
+    assert!(matches!(food_production.part_one(), Err(Error::MissingSection(section)) if section == "soil-to-fertilizer map"));
+}
+
+#[test]
+fn test_unsolvable() {
+    let haunted_wasteland = HauntedWasteland::parse_str("LR\n\n11A = (11Z, 11Z)\n11Z = (11Z, 11Z)\n").unwrap();
+    assert!(matches!(haunted_wasteland.part_one(), Err(Error::Unsolvable(_))));
+    assert_eq!(haunted_wasteland.part_two().unwrap(), 1);
+
+    let unsolvable = |input: &str| HauntedWasteland::parse_str(input).unwrap();
+    assert!(matches!(unsolvable("L\n\n11A = (XXX, XXX)\n").part_two(),
+        Err(Error::Unsolvable(message)) if message == "node 'XXX' is not defined"));
+    assert!(matches!(unsolvable("L\n\n11A = (11B, 11B)\n11B = (11A, 11A)\n").part_two(),
+        Err(Error::Unsolvable(message)) if message == "'11A' never reaches a node ending in 'Z'"));
+    assert!(matches!(unsolvable("L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n").part_one(),
+        Err(Error::Unsolvable(message)) if message == "'AAA' never reaches 'ZZZ'"));
+
+    // Ghost cycles of the primes below 60 have a least common multiple
+    // beyond u64.
+    let mut ghosts = "L\n\n".to_string();
+    for (ghost, prime) in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59].iter().enumerate() {
+        let letter = (b'B' + ghost as u8) as char;
+        let mut path = vec![format!("{}0A", letter)];
+        path.extend((1..*prime).map(|step| format!("{}{:02}", letter, step)));
+        path.push(format!("{}0Z", letter));
+        for pair in path.windows(2) {
+            ghosts.push_str(&format!("{} = ({}, {})\n", pair[0], pair[1], pair[1]));
+        }
+        ghosts.push_str(&format!("{} = ({}, {})\n", path[path.len() - 1], path[1], path[1]));
+    }
+    assert!(matches!(unsolvable(&ghosts).part_two(), Err(Error::Unsolvable(message)) if message.contains("overflows")));
+
+    let boat_race = BoatRace::parse_str("Time: 3\nDistance: 10\n").unwrap();
+    assert!(matches!(boat_race.part_one(), Err(Error::Unsolvable(_))));
+
+    let numbers: Vec<String> = (1..=70).map(|number| number.to_string()).collect();
+    let card = format!("Card 1: {} | {}\n", numbers.join(" "), numbers.join(" "));
+    let scratchcards = Scratchcards::parse_str(&card).unwrap();
+    assert!(matches!(scratchcards.part_one(), Err(Error::Unsolvable(_))));
+
+    // Every card wins a copy of each card after it, doubling the copies.
+    let cards: String = (1..=70)
+        .map(|id| {
+            let matching = numbers[..70 - id].join(" ");
+            format!("Card {}: {} 100 | {} 200\n", id, matching, matching)
+        })
+        .collect();
+    let scratchcards = Scratchcards::parse_str(&cards).unwrap();
+    assert!(matches!(scratchcards.part_two(), Err(Error::Unsolvable(_))));
+
+    let camel_cards = CamelCards::parse_str("AAAAA 18446744073709551615\nKKKKK 2\n").unwrap();
+    assert!(matches!(camel_cards.part_one(), Err(Error::Unsolvable(message)) if message == "total winnings overflow u64"));
+    assert!(matches!(camel_cards.reference_part_two(), Some(Err(Error::Unsolvable(_)))));
+}
+
+#[test]
+fn test_edge_inputs_do_not_panic() {
+    // Holding for half the race only ties the record.
+    let boat_race = BoatRace::parse_str("Time: 10\nDistance: 25\n").unwrap();
+    assert_eq!((boat_race.part_one().unwrap(), boat_race.part_two().unwrap()), (0, 0));
+
+    let almanac = std::fs::read_to_string("input_5_short").unwrap().replacen("seeds: 79 14", "seeds: 79 0", 1);
+    let food_production = FoodProduction::parse_str(&almanac).unwrap();
+    assert_eq!(food_production.part_two().unwrap(), food_production.reference_part_two().unwrap().unwrap());
+
+    let scratchcards = Scratchcards::parse_str("Card 1: 5 | 5\n").unwrap();
+    assert_eq!((scratchcards.part_one().unwrap(), scratchcards.part_two().unwrap()), (1, 1));
+}
+
+#[test]
+fn test_parse_error_offending_text() {
+    match Scratchcards::parse_str("Card 1: 41 48 | 83 8x\n") {
+        Err(Error::Parse { line, column, text, line_text, .. }) => {
+            assert_eq!((line, column), (1, 20));
+            assert_eq!(text, "8x");
+            assert_eq!(line_text, "Card 1: 41 48 | 83 8x");
+        }
+        _ => panic!("expected a parse error"),
+    }
+    match HauntedWasteland::parse_str("LR\n\nAAA = (BBB; CCC)\n") {
+        Err(Error::Parse { line, column, text, .. }) => assert_eq!((line, column, text.as_str()), (3, 11, ";")),
+        _ => panic!("expected a parse error"),
+    }
+}
@@ -0,0 +1,34 @@
+use advent_of_code_2023::generate::{GenOptions, Rng};
+use advent_of_code_2023::registry::DAYS;
+
+#[test]
+fn test_rng_is_deterministic() {
+    let mut first = Rng::new(42);
+    let mut second = Rng::new(42);
+    let first: Vec<usize> = (0..16).map(|_| first.range(0..1000)).collect();
+    let second: Vec<usize> = (0..16).map(|_| second.range(0..1000)).collect();
+    assert_eq!(first, second);
+    assert!(first.iter().all(|&value| value < 1000));
+    assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+}
+
+#[test]
+fn test_generated_inputs_are_solvable() {
+    let shapes = [GenOptions { size: 1, width: 1 }, GenOptions { size: 5, width: 3 }, GenOptions::default()];
+    // Days scaffolded by new-day have no generator yet.
+    for (day, generate) in DAYS.iter().filter_map(|day| Some((day, day.generate?))) {
+        for seed in 0..3 {
+            for options in &shapes {
+                let input = generate(&mut Rng::new(seed), options);
+                assert_eq!(input, generate(&mut Rng::new(seed), options), "day {} is not deterministic", day.number);
+                let solution = (day.parse)(&mut input.as_bytes())
+                    .unwrap_or_else(|e| panic!("day {} seed {}: {}\n{}", day.number, seed, e, input));
+                for (part, outcome) in [(1, solution.part_one()), (2, solution.part_two())] {
+                    if let Err(e) = outcome {
+                        panic!("day {} part {} seed {} size {}: {}", day.number, part, seed, options.size, e);
+                    }
+                }
+            }
+        }
+    }
+}
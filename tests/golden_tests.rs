@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+use advent_of_code_2023::answers::Answers;
+use advent_of_code_2023::input::Source;
+use advent_of_code_2023::registry::DAYS;
+use advent_of_code_2023::runner::run_day;
+
+const EXAMPLE_ANSWERS: &str = "answers_short";
+
+fn example_path(day: u32) -> PathBuf {
+    PathBuf::from(format!("input_{}_short", day))
+}
+
+fn describe(outcome: &Result<u64, String>) -> String {
+    match outcome {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("error ({})", e),
+    }
+}
+
+#[test]
+fn test_examples_match_expected_answers() {
+    let answers = Answers::load(Path::new(EXAMPLE_ANSWERS)).unwrap();
+    let mut mismatches = Vec::new();
+    for day in DAYS {
+        let path = example_path(day.number);
+        if !path.exists() {
+            mismatches.push(format!("day {} ({}): missing example file {}", day.number, day.name, path.display()));
+            continue;
+        }
+        let report = run_day(day, &Source::File(path), None);
+        for part in &report.parts {
+            let expected = answers.get(day.number, part.part);
+            let matches = match (expected, &part.outcome) {
+                (Some(expected), Ok(actual)) => expected == *actual,
+                (None, Err(_)) => true,
+                _ => false,
+            };
+            if !matches {
+                mismatches.push(format!(
+                    "day {} ({}) part {}:\n    expected: {}\n    actual:   {}",
+                    day.number,
+                    day.name,
+                    part.part,
+                    expected.map_or("an error".to_string(), |answer| answer.to_string()),
+                    describe(&part.outcome)));
+            }
+        }
+    }
+    assert!(mismatches.is_empty(), "{} example answer(s) differ from {}:\n{}\n",
+        mismatches.len(), EXAMPLE_ANSWERS, mismatches.join("\n"));
+}
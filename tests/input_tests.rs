@@ -0,0 +1,47 @@
+use advent_of_code_2023::food_production::FoodProduction;
+use advent_of_code_2023::input::{self, Source};
+use advent_of_code_2023::registry;
+use advent_of_code_2023::runner;
+use advent_of_code_2023::scratchcards::Scratchcards;
+use advent_of_code_2023::solution::Solution;
+
+const SCRATCHCARDS: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+#[test]
+fn test_parse_str() {
+    let scratchcards = Scratchcards::parse_str(SCRATCHCARDS).unwrap();
+    assert_eq!(scratchcards.part_one().unwrap(), 13);
+    assert_eq!(scratchcards.part_two().unwrap(), 30);
+}
+
+#[test]
+fn test_run_day_from_text() {
+    let day = registry::find_day(4).unwrap();
+    let report = runner::run_day(day, &Source::Text(SCRATCHCARDS.to_string()), None);
+    let answers: Vec<u64> = report.parts.into_iter().map(|part| part.outcome.unwrap()).collect();
+    assert_eq!(answers, vec![13, 30]);
+    assert_eq!(report.input.as_deref(), Some("<text>"));
+}
+
+#[test]
+fn test_lines_are_normalized() {
+    let lines = input::lines("\u{feff}seeds: 79 14 \r\n\r\nseed-to-soil map:\t\r\n".as_bytes())
+        .collect::<std::io::Result<Vec<String>>>()
+        .unwrap();
+    assert_eq!(lines, vec!["seeds: 79 14", "", "seed-to-soil map:"]);
+}
+
+#[test]
+fn test_parse_windows_input() {
+    let windows_input = format!("\u{feff}{}", include_str!("../input_5_short").replace('\n', " \r\n"));
+    let food_production = FoodProduction::parse_str(&windows_input).unwrap();
+    assert_eq!(food_production.part_one().unwrap(), 35);
+    assert_eq!(food_production.part_two().unwrap(), 46);
+}
@@ -0,0 +1,27 @@
+use advent_of_code_2023::matcher::{Match, Matcher};
+use advent_of_code_2023::trebuchet::first_last_numbers;
+
+#[test]
+fn test_overlapping_words() {
+    let matcher = Matcher::new(&["one", "two", "eight", "nine"]);
+    assert_eq!(matcher.first("xeightwonine"), Some(Match { pattern: 2, start: 1, end: 6 }));
+    assert_eq!(matcher.last("xeightwonine"), Some(Match { pattern: 3, start: 8, end: 12 }));
+    assert_eq!(matcher.last("eightwo"), Some(Match { pattern: 1, start: 4, end: 7 }));
+    assert_eq!(matcher.first("nothing here"), None);
+    assert_eq!(matcher.last(""), None);
+
+    assert_eq!(first_last_numbers("eightwo"), 82);
+    assert_eq!(first_last_numbers("zoneight234"), 14);
+    assert_eq!(first_last_numbers("7pqrstsixteen"), 76);
+    assert_eq!(first_last_numbers("oneighthreeightwo"), 12);
+    assert_eq!(first_last_numbers("no digits"), 0);
+}
+
+#[test]
+fn test_nested_patterns() {
+    // "bc" ends first but "abcd" starts first; "cd" and "c" start at the same byte.
+    let matcher = Matcher::new(&["abcd", "bc", "c", "cd"]);
+    assert_eq!(matcher.first("xabcdx"), Some(Match { pattern: 0, start: 1, end: 5 }));
+    assert_eq!(matcher.last("xabcdx"), Some(Match { pattern: 3, start: 3, end: 5 }));
+    assert_eq!(matcher.first("ébcé"), Some(Match { pattern: 1, start: 2, end: 4 }));
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use advent_of_code_2023::cube_conundrum::CubeConundrum;
+use advent_of_code_2023::output::{render_csv, render_json, render_snippet};
+use advent_of_code_2023::solution::Solution;
+use advent_of_code_2023::runner::{DayReport, PartReport};
+
+fn sample_reports() -> Vec<DayReport> {
+    vec![DayReport {
+        day: 8,
+        name: "haunted_wasteland",
+        input: None,
+        parse_elapsed: Duration::from_nanos(1500),
+        parts: vec![
+            PartReport { part: 1, outcome: Err("'AAA' or \"ZZZ\", missing".to_string()), elapsed: Duration::from_nanos(20) },
+            PartReport { part: 2, outcome: Ok(6), elapsed: Duration::from_nanos(300) },
+        ],
+        diagnostics: Vec::new(),
+        parse_error: None,
+    }]
+}
+
+#[test]
+fn test_render_json() {
+    let expected = "[\n\
+        \x20 {\"day\": 8, \"name\": \"haunted_wasteland\", \"part\": 1, \"answer\": null, \"error\": \"'AAA' or \\\"ZZZ\\\", missing\", \"parse_ns\": 1500, \"elapsed_ns\": 20},\n\
+        \x20 {\"day\": 8, \"name\": \"haunted_wasteland\", \"part\": 2, \"answer\": 6, \"error\": null, \"parse_ns\": 1500, \"elapsed_ns\": 300}\n\
+        ]\n";
+    assert_eq!(render_json(&sample_reports()), expected);
+}
+
+#[test]
+fn test_render_csv() {
+    let expected = "day,name,part,answer,error,parse_ns,elapsed_ns\n\
+        8,haunted_wasteland,1,,\"'AAA' or \"\"ZZZ\"\", missing\",1500,20\n\
+        8,haunted_wasteland,2,6,,1500,300\n";
+    assert_eq!(render_csv(&sample_reports()), expected);
+}
+
+#[test]
+fn test_render_snippet() {
+    let error = CubeConundrum::parse_str("Game 1: 3 blue\nGame 2: 3 blue, 4 rde\n").err().unwrap();
+    let expected = " --> input_2:2:19\n\
+        \x20 |\n\
+        2 | Game 2: 3 blue, 4 rde\n\
+        \x20 |                   ^^^ invalid color 'rde'\n";
+    assert_eq!(render_snippet("input_2", &error).unwrap(), expected);
+}
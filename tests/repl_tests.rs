@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use advent_of_code_2023::camel_cards::CamelCards;
+use advent_of_code_2023::food_production::FoodProduction;
+use advent_of_code_2023::haunted_wasteland::HauntedWasteland;
+use advent_of_code_2023::repl::{evaluate, run};
+use advent_of_code_2023::solution::Solution;
+
+#[test]
+fn test_evaluate_queries() {
+    let food_production = FoodProduction::parse_file(Path::new("input_5_short")).unwrap();
+    assert_eq!(evaluate(&food_production, "location 79").unwrap(),
+        "Seed 79 -> Soil 81 -> Fertilizer 81 -> Water 81 -> Light 74 -> Temperature 78 -> Humidity 78 -> Location 82\n");
+    assert_eq!(evaluate(&food_production, "location").unwrap(), "error: invalid query: missing <seed>\n");
+    assert_eq!(evaluate(&food_production, "part2").unwrap(), "46\n");
+    assert_eq!(evaluate(&food_production, "  ").unwrap(), "");
+    assert_eq!(evaluate(&food_production, "quit"), None);
+
+    let camel_cards = CamelCards::parse_file(Path::new("input_7_short")).unwrap();
+    assert_eq!(evaluate(&camel_cards, "hand KTJJT").unwrap(), "KTJJT: TwoPairs('T', 'J'), with jokers FourOfAKind('T')\n");
+
+    let wasteland = HauntedWasteland::parse_file(Path::new("input_8_short")).unwrap();
+    assert_eq!(evaluate(&wasteland, "walk 11A 1000").unwrap(), "11Z\n");
+    assert_eq!(evaluate(&wasteland, "steps 22A").unwrap(), "3\n");
+    assert_eq!(evaluate(&wasteland, "jump 11A").unwrap(), "error: invalid query: unknown command 'jump', try 'help'\n");
+}
+
+#[test]
+fn test_run_session() {
+    let wasteland = HauntedWasteland::parse_file(Path::new("input_8_short")).unwrap();
+    let mut output = Vec::new();
+    run(&wasteland, "> ", "node 11A\nexit\nnode 22A\n".as_bytes(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "> 11A = (11B, XXX)\n> ");
+}
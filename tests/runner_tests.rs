@@ -0,0 +1,52 @@
+use std::fs;
+use std::io::BufRead;
+
+use advent_of_code_2023::error::Result;
+use advent_of_code_2023::registry::{find_day, Day, DAYS};
+use advent_of_code_2023::runner::{run_all, run_days};
+use advent_of_code_2023::solution::Solution;
+
+fn parse_panics(_reader: &mut dyn BufRead) -> Result<Box<dyn Solution>> {
+    panic!("deliberate test panic")
+}
+
+#[test]
+fn test_run_all_keeps_order() {
+    let dir = std::env::temp_dir().join(format!("runner_tests_all_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("input_1"), "1abc2\n").unwrap();
+
+    let reports = run_all(&dir, 4);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let days: Vec<u32> = reports.iter().map(|report| report.day).collect();
+    let registered: Vec<u32> = DAYS.iter().map(|day| day.number).collect();
+    assert_eq!(days, registered);
+    assert_eq!(reports[0].parts[0].outcome, Ok(12));
+    assert!(reports[1].parts[0].outcome.as_ref().unwrap_err().starts_with("No input for day 2"));
+}
+
+#[test]
+fn test_run_days_isolates_panics() {
+    let dir = std::env::temp_dir().join(format!("runner_tests_panics_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("input_1"), "1abc2\n").unwrap();
+    fs::write(dir.join("input_9"), "anything\n").unwrap();
+
+    let trebuchet = find_day(1).unwrap();
+    let days = [
+        Day { number: 1, name: trebuchet.name, parse: trebuchet.parse, generate: None },
+        Day { number: 9, name: "panics", parse: parse_panics, generate: None },
+        Day { number: 10, name: "missing", parse: parse_panics, generate: None },
+    ];
+    let reports = run_days(&days, &dir, 3);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let numbers: Vec<u32> = reports.iter().map(|report| report.day).collect();
+    assert_eq!(numbers, vec![1, 9, 10]);
+    assert_eq!(reports[0].parts[0].outcome, Ok(12));
+    let panicked = reports[1].parts[1].outcome.as_ref().unwrap_err();
+    assert!(panicked.starts_with("Panicked while solving"), "{}", panicked);
+    assert!(panicked.ends_with("deliberate test panic"), "{}", panicked);
+    assert!(reports[2].parts[0].outcome.as_ref().unwrap_err().starts_with("No input for day 10"));
+}
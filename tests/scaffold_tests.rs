@@ -0,0 +1,63 @@
+use std::fs;
+
+use advent_of_code_2023::scaffold::{is_valid_name, register_day, register_module, scaffold, type_name};
+
+const REGISTRY: &str = "\
+use crate::trebuchet::Trebuchet;
+use crate::boat_race::BoatRace;
+
+pub static DAYS: &[Day] = &[
+    Day { number: 1, name: \"trebuchet\", parse: parse_boxed::<Trebuchet> },
+    Day { number: 6, name: \"boat_race\", parse: parse_boxed::<BoatRace> },
+];
+";
+
+#[test]
+fn test_names() {
+    assert_eq!(type_name("mirage_maintenance"), "MirageMaintenance");
+    assert!(is_valid_name("pipe_maze2"));
+    assert!(!is_valid_name("PipeMaze"));
+    assert!(!is_valid_name("9_lives"));
+    assert!(!is_valid_name("pipe_"));
+}
+
+#[test]
+fn test_register_day_keeps_days_in_order() {
+    let expected = "\
+use crate::trebuchet::Trebuchet;
+use crate::boat_race::BoatRace;
+use crate::wait_for_it::WaitForIt;
+
+pub static DAYS: &[Day] = &[
+    Day { number: 1, name: \"trebuchet\", parse: parse_boxed::<Trebuchet> },
+    Day { number: 5, name: \"wait_for_it\", parse: parse_boxed::<WaitForIt>, generate: None },
+    Day { number: 6, name: \"boat_race\", parse: parse_boxed::<BoatRace> },
+];
+";
+    assert_eq!(register_day(REGISTRY, 5, "wait_for_it").unwrap(), expected);
+    assert_eq!(register_module("pub mod trebuchet;\npub mod solution;\n", "wait_for_it").unwrap(),
+        "pub mod trebuchet;\npub mod wait_for_it;\npub mod solution;\n");
+}
+
+#[test]
+fn test_scaffold_writes_files() {
+    let root = std::env::temp_dir().join(format!("scaffold_tests_{}", std::process::id()));
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("tests")).unwrap();
+    fs::copy("src/lib.rs", root.join("src/lib.rs")).unwrap();
+    fs::copy("src/registry.rs", root.join("src/registry.rs")).unwrap();
+
+    let written = scaffold(&root, 42, "deep_thought").unwrap();
+    let module = fs::read_to_string(root.join("src/deep_thought.rs")).unwrap();
+    let lib_rs = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+    let registry_rs = fs::read_to_string(root.join("src/registry.rs")).unwrap();
+    let repeated = scaffold(&root, 43, "deep_thought");
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(written.len(), 5);
+    assert!(module.contains("impl Solution for DeepThought"));
+    assert!(module.contains("pub fn run_day_42<P>"));
+    assert!(lib_rs.contains("pub mod deep_thought;\npub mod solution;"));
+    assert!(registry_rs.contains("Day { number: 42, name: \"deep_thought\", parse: parse_boxed::<DeepThought>, generate: None },\n];"));
+    assert!(repeated.is_err());
+}
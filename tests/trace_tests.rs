@@ -0,0 +1,21 @@
+use advent_of_code_2023::trace;
+
+#[test]
+fn test_trace_levels() {
+    assert!(!trace::enabled(1));
+
+    trace::set_level(1);
+    assert!(trace::enabled(1));
+    assert!(!trace::enabled(2));
+    assert!(!trace::enabled(0));
+
+    trace::set_level(9);
+    assert_eq!(trace::level(), trace::MAX_LEVEL);
+    assert!(trace::enabled(2));
+
+    assert!(!trace::silenced(|| trace::enabled(1)));
+    assert!(trace::enabled(1));
+
+    trace::set_level(0);
+    assert!(!trace::enabled(1));
+}
@@ -0,0 +1,91 @@
+use advent_of_code_2023::solution::Solution;
+use advent_of_code_2023::output::render_calibration;
+use advent_of_code_2023::trebuchet::{Calibration, CalibrationRecord, MissingDigitPolicy, Token, TokenKind, Trebuchet, Vocabulary};
+
+#[test]
+fn test_custom_vocabulary() {
+    let vocabulary = Vocabulary::parse("zero 0\nuno 1  # alias\n\ndos 2\n".as_bytes()).unwrap();
+    let words: Vec<(&str, u32)> = vocabulary.words().iter().map(|(word, value)| (word.as_str(), *value)).collect();
+    assert_eq!(words, vec![("zero", 0), ("uno", 1), ("dos", 2)]);
+    assert_eq!(vocabulary.calibration_value("xunozerox"), Some(10));
+    assert_eq!(vocabulary.calibration_value("one7dos"), Some(72));
+    assert_eq!(vocabulary.calibration_value("three"), None);
+
+    let trebuchet = Trebuchet::parse_str("xunozerox\ndos5\ntwo\n").unwrap();
+    assert_eq!(trebuchet.part_two().unwrap(), 55 + 22);
+    let trebuchet = trebuchet.with_vocabulary(vocabulary);
+    assert_eq!(trebuchet.part_two().unwrap(), 10 + 25);
+    assert_eq!(trebuchet.reference_part_two().unwrap().unwrap(), 10 + 25);
+
+    let kinds: Vec<(TokenKind, TokenKind)> = trebuchet.records(true)
+        .filter_map(|record| Some((record.first?.kind, record.last?.kind)))
+        .collect();
+    assert_eq!(kinds, vec![(TokenKind::Word, TokenKind::Word), (TokenKind::Word, TokenKind::Digit)]);
+}
+
+#[test]
+fn test_vocabulary_collisions() {
+    let error = Vocabulary::new(&[("one", 1), ("three", 3), ("thre", 2)]).err().unwrap();
+    assert_eq!(error.to_string(), "invalid vocabulary: 'thre' (2) and 'three' (3) overlap with different values");
+    let error = Vocabulary::new(&[("4ever", 3)]).err().unwrap();
+    assert_eq!(error.to_string(), "invalid vocabulary: '4ever' (3) and '4' (4) overlap with different values");
+    let error = Vocabulary::new(&[("uno", 1), ("uno", 1)]).err().unwrap();
+    assert_eq!(error.to_string(), "invalid vocabulary: 'uno' is defined more than once");
+    assert!(Vocabulary::new(&[("ein", 1), ("eins", 1), ("eight", 8), ("two", 2)]).is_ok());
+
+    let error = Vocabulary::parse("uno 1\nzehn 10\n".as_bytes()).err().unwrap();
+    assert_eq!(error.to_string(), "line 2, column 1: value 10 of 'zehn' is not a single digit");
+    let error = Vocabulary::parse("uno\n".as_bytes()).err().unwrap();
+    assert_eq!(error.to_string(), "line 1, column 1: expected '<word> <digit>', got 'uno'");
+}
+
+#[test]
+fn test_missing_digit_policies() {
+    let trebuchet = Trebuchet::parse_str("two1\nnothing\nthree\n").unwrap();
+    assert_eq!(trebuchet.missing_digits(), MissingDigitPolicy::Skip);
+    assert_eq!(trebuchet.calibrate(false).unwrap(), Calibration { sum: 11, counted: 1, missing: vec![2, 3] });
+    assert_eq!(trebuchet.calibrate(true).unwrap(), Calibration { sum: 54, counted: 2, missing: vec![2] });
+    assert_eq!(trebuchet.diagnostics(), vec![
+        ("lines_without_digits", "[2, 3]".to_string()),
+        ("lines_without_digits_or_words", "[2]".to_string()),
+    ]);
+
+    let trebuchet = trebuchet.with_missing_digits(MissingDigitPolicy::Zero);
+    assert_eq!(trebuchet.calibrate(true).unwrap(), Calibration { sum: 54, counted: 3, missing: vec![2] });
+
+    let trebuchet = trebuchet.with_missing_digits(MissingDigitPolicy::Error);
+    assert_eq!(trebuchet.part_one().unwrap_err().to_string(), "line 2, column 1: no digit in line");
+    assert_eq!(trebuchet.part_two().unwrap_err().to_string(), "line 2, column 1: no digit or digit word in line");
+    assert_eq!(trebuchet.reference_part_two().unwrap().unwrap_err().to_string(),
+        "line 2, column 1: no digit or digit word in line");
+
+    assert_eq!("zero".parse::<MissingDigitPolicy>(), Ok(MissingDigitPolicy::Zero));
+    assert_eq!("lenient".parse::<MissingDigitPolicy>(), Err("Invalid policy 'lenient', expected skip, zero or error".to_string()));
+}
+
+#[test]
+fn test_calibration_records() {
+    let trebuchet = Trebuchet::parse_str("éightwo3\nnothing\n").unwrap();
+    let records: Vec<CalibrationRecord> = trebuchet.records(true).collect();
+    let token = |text, kind, value, byte_offset, char_offset| Some(Token { text, kind, value, byte_offset, char_offset });
+    assert_eq!(records, vec![
+        CalibrationRecord {
+            line: 1,
+            text: "éightwo3",
+            first: token("two", TokenKind::Word, 2, 5, 4),
+            last: token("3", TokenKind::Digit, 3, 8, 7),
+        },
+        CalibrationRecord { line: 2, text: "nothing", first: None, last: None },
+    ]);
+    assert_eq!(records[0].value(), Some(23));
+    assert_eq!(records[1].value(), None);
+
+    let digits: Vec<Option<u32>> = trebuchet.records(false).map(|record| record.value()).collect();
+    assert_eq!(digits, vec![Some(33), None]);
+
+    assert_eq!(render_calibration(&records), "\
+Line  Value  First     Kind   Byte  Char  Last      Kind   Byte  Char
+   1     23  two       word      5     4  3         digit     8     7
+   2      -  -         -         -     -  -         -         -     -
+");
+}
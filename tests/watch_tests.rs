@@ -0,0 +1,18 @@
+use std::fs;
+
+use advent_of_code_2023::watch::Watcher;
+
+#[test]
+fn test_watcher_detects_changes() {
+    let path = std::env::temp_dir().join(format!("watch_tests_{}", std::process::id()));
+    fs::write(&path, "Time: 7\n").unwrap();
+    let mut watcher = Watcher::new(vec![path.clone()]);
+    assert!(!watcher.changed());
+
+    fs::write(&path, "Time: 7 15\n").unwrap();
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+
+    fs::remove_file(&path).unwrap();
+    assert!(watcher.changed());
+}